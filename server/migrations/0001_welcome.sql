@@ -0,0 +1,52 @@
+-- Existing deployments created these tables by hand, so every statement here
+-- must be safe to run against a database that already has them.
+CREATE TABLE IF NOT EXISTS "welcome" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL DEFAULT 0 UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+CREATE TABLE IF NOT EXISTS "welcome_join" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"welcome_id"	INTEGER NOT NULL UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	"channel_id"	TEXT,
+	"message_type"	TEXT NOT NULL DEFAULT 'text',
+	"content"	TEXT,
+	"embed"	TEXT,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	FOREIGN KEY("welcome_id") REFERENCES "welcome"("id")
+);
+
+CREATE TABLE IF NOT EXISTS "welcome_join_dm" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"welcome_id"	INTEGER NOT NULL UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	"channel_id"	TEXT,
+	"message_type"	TEXT NOT NULL DEFAULT 'text',
+	"content"	TEXT,
+	"embed"	TEXT,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	FOREIGN KEY("welcome_id") REFERENCES "welcome"("id")
+);
+
+CREATE TABLE IF NOT EXISTS "welcome_join_roles" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"welcome_id"	INTEGER NOT NULL UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	"roles"	TEXT,
+	"delay"	NUMERIC NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	FOREIGN KEY("welcome_id") REFERENCES "welcome"("id")
+);
+
+CREATE TABLE IF NOT EXISTS "welcome_leave" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"welcome_id"	INTEGER NOT NULL UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	"channel_id"	TEXT,
+	"content"	TEXT,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	FOREIGN KEY("welcome_id") REFERENCES "welcome"("id")
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS "reaction_roles" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL UNIQUE,
+	"enabled"	NUMERIC NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+CREATE TABLE IF NOT EXISTS "reaction_roles_message" (
+	"message_id"	TEXT NOT NULL UNIQUE,
+	"channel_id"	TEXT NOT NULL,
+	"guild_id"	TEXT NOT NULL,
+	"content"	TEXT NOT NULL DEFAULT '',
+	"embeds"	TEXT NOT NULL DEFAULT '[]',
+	"roles"	TEXT NOT NULL DEFAULT '{}',
+	PRIMARY KEY("message_id")
+);
+
+CREATE INDEX IF NOT EXISTS "reaction_roles_message_guild_id" ON "reaction_roles_message" ("guild_id");
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS "poll" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"author_id"	TEXT NOT NULL,
+	"question"	TEXT NOT NULL,
+	"start"	DATETIME,
+	"ends"	DATETIME,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+CREATE TABLE IF NOT EXISTS "poll_option" (
+	"guild_id"	TEXT NOT NULL UNIQUE,
+	"positive_id"	TEXT NOT NULL,
+	"positive_name"	TEXT NOT NULL,
+	"negative_id"	TEXT NOT NULL,
+	"negative_name"	TEXT NOT NULL,
+	PRIMARY KEY("guild_id")
+);
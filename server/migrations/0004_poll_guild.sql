@@ -0,0 +1,3 @@
+ALTER TABLE "poll" ADD COLUMN "guild_id" TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX IF NOT EXISTS "poll_guild_id" ON "poll" ("guild_id");
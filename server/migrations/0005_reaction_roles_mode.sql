@@ -0,0 +1,2 @@
+ALTER TABLE "reaction_roles_message" ADD COLUMN "mode" TEXT NOT NULL DEFAULT 'normal';
+ALTER TABLE "reaction_roles_message" ADD COLUMN "role_limit" INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE "reaction_roles_message" ADD COLUMN "style" TEXT NOT NULL DEFAULT 'reactions';
@@ -0,0 +1,3 @@
+ALTER TABLE "poll" ADD COLUMN "channel_id" TEXT;
+ALTER TABLE "poll" ADD COLUMN "message_id" TEXT;
+ALTER TABLE "poll" ADD COLUMN "closed" NUMERIC NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS "poll_choice" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"poll_id"	INTEGER NOT NULL,
+	"position"	INTEGER NOT NULL,
+	"emoji"	TEXT NOT NULL,
+	"name"	TEXT NOT NULL,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	FOREIGN KEY("poll_id") REFERENCES "poll"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "poll_choice_poll_id" ON "poll_choice" ("poll_id");
@@ -0,0 +1,10 @@
+ALTER TABLE "poll" ADD COLUMN "voting" TEXT NOT NULL DEFAULT 'reactions';
+ALTER TABLE "poll" ADD COLUMN "hide_results" NUMERIC NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS "poll_vote" (
+	"poll_id"	INTEGER NOT NULL,
+	"user_id"	TEXT NOT NULL,
+	"position"	INTEGER NOT NULL,
+	PRIMARY KEY("poll_id", "user_id"),
+	FOREIGN KEY("poll_id") REFERENCES "poll"("id") ON DELETE CASCADE
+);
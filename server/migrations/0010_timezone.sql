@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS "guild_timezone" (
+	"guild_id"	TEXT NOT NULL UNIQUE,
+	"timezone"	TEXT NOT NULL,
+	PRIMARY KEY("guild_id")
+);
+
+CREATE TABLE IF NOT EXISTS "user_timezone" (
+	"user_id"	TEXT NOT NULL UNIQUE,
+	"timezone"	TEXT NOT NULL,
+	PRIMARY KEY("user_id")
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS "command_permission" (
+	"guild_id"	TEXT NOT NULL,
+	"command"	TEXT NOT NULL,
+	"permissions"	INTEGER NOT NULL DEFAULT 0,
+	"roles"	TEXT NOT NULL DEFAULT '[]',
+	PRIMARY KEY("guild_id", "command")
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS "custom_commands" (
+	"guild_id"	TEXT NOT NULL UNIQUE,
+	"prefix"	TEXT NOT NULL DEFAULT '!',
+	PRIMARY KEY("guild_id")
+);
+
+CREATE TABLE IF NOT EXISTS "custom_command" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL,
+	"name"	TEXT NOT NULL,
+	"description"	TEXT NOT NULL DEFAULT '',
+	"content"	TEXT NOT NULL DEFAULT '',
+	"embeds"	TEXT NOT NULL DEFAULT '[]',
+	"slash"	NUMERIC NOT NULL DEFAULT 1,
+	"prefix"	NUMERIC NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	UNIQUE("guild_id", "name")
+);
@@ -0,0 +1,6 @@
+ALTER TABLE "custom_command" ADD COLUMN "reply_target" TEXT NOT NULL DEFAULT '{"type":"channel"}';
+ALTER TABLE "custom_command" ADD COLUMN "add_roles" TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE "custom_command" ADD COLUMN "remove_roles" TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE "custom_command" ADD COLUMN "delete_trigger" NUMERIC NOT NULL DEFAULT 0;
+ALTER TABLE "custom_command" ADD COLUMN "allowed_channels" TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE "custom_command" ADD COLUMN "allowed_roles" TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS "auto_responder_rule" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL,
+	"trigger"	TEXT NOT NULL,
+	"match_kind"	TEXT NOT NULL DEFAULT 'contains',
+	"case_sensitive"	NUMERIC NOT NULL DEFAULT 0,
+	"channels"	TEXT NOT NULL DEFAULT '[]',
+	"content"	TEXT NOT NULL DEFAULT '',
+	"embed"	TEXT,
+	"reactions"	TEXT NOT NULL DEFAULT '[]',
+	"cooldown"	INTEGER NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
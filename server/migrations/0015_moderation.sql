@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS "moderation" (
+	"guild_id"	TEXT NOT NULL UNIQUE,
+	"mute_role_id"	TEXT,
+	"dm_target"	NUMERIC NOT NULL DEFAULT 0,
+	"dm_template"	TEXT NOT NULL DEFAULT 'You were {action} in {server.name}. Reason: {reason}',
+	PRIMARY KEY("guild_id")
+);
+
+CREATE TABLE IF NOT EXISTS "mod_case" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL,
+	"case_number"	INTEGER NOT NULL,
+	"action"	TEXT NOT NULL,
+	"target_id"	TEXT NOT NULL,
+	"moderator_id"	TEXT NOT NULL,
+	"reason"	TEXT NOT NULL DEFAULT '',
+	"duration"	INTEGER,
+	"created_at"	DATETIME NOT NULL,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	UNIQUE("guild_id", "case_number")
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS "scheduled_job" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL,
+	"job"	TEXT NOT NULL,
+	"due_at"	DATETIME NOT NULL,
+	"attempts"	INTEGER NOT NULL DEFAULT 0,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+ALTER TABLE "mod_case" ADD COLUMN "expired_at" DATETIME;
@@ -0,0 +1,2 @@
+ALTER TABLE "moderation" ADD COLUMN "moderator_roles" TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE "moderation" ADD COLUMN "admin_roles" TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS "warn_threshold" (
+	"id"	INTEGER NOT NULL UNIQUE,
+	"guild_id"	TEXT NOT NULL,
+	"warnings"	INTEGER NOT NULL,
+	"period"	INTEGER,
+	"action"	TEXT NOT NULL,
+	"duration"	INTEGER,
+	PRIMARY KEY("id" AUTOINCREMENT),
+	UNIQUE("guild_id", "warnings")
+);
+
+ALTER TABLE "mod_case" ADD COLUMN "related_cases" TEXT NOT NULL DEFAULT '[]';
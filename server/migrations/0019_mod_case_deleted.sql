@@ -0,0 +1,3 @@
+-- Deleted cases are kept so their numbers are never handed out again, and so a deleted
+-- tempban or mute is still lifted when it expires.
+ALTER TABLE "mod_case" ADD COLUMN "deleted_at" DATETIME;
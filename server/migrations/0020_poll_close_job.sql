@@ -0,0 +1,6 @@
+-- Polls are now closed by the job scheduler, so give every open poll with an end time the
+-- job that used to be set up in memory at startup.
+INSERT INTO "scheduled_job" ("guild_id", "job", "due_at")
+SELECT "guild_id", '{"type":"closePoll","pollId":' || "id" || '}', "ends"
+FROM "poll"
+WHERE "closed" = false AND "ends" IS NOT NULL AND "message_id" IS NOT NULL;
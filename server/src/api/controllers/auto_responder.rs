@@ -0,0 +1,133 @@
+use std::convert::Infallible;
+
+use twilight_model::id::GuildId;
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{models::auto_responder::AutoResponderRuleRequestData, util},
+    db::Database,
+    modules::auto_responder::AutoResponderRule,
+};
+
+pub async fn fetch_rules_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .auto_responder()
+        .fetch_rules_for_guild(GuildId(guild_id))
+        .await
+    {
+        Ok(rules) => Ok(warp::reply::json(&rules).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch auto responder rules: {:?}", err),
+        )),
+    }
+}
+
+pub async fn create_rule_for_guild(
+    guild_id: u64,
+    data: AutoResponderRuleRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut rule = AutoResponderRule {
+        id: 0,
+        guild_id: GuildId(guild_id),
+        trigger: data.trigger,
+        match_kind: data.match_kind,
+        case_sensitive: data.case_sensitive,
+        channels: data.channels,
+        content: data.content,
+        embed: data.embed,
+        reactions: data.reactions,
+        cooldown: data.cooldown,
+    };
+    if let Err(err) = rule.validate() {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    match db.auto_responder().create_rule(&mut rule).await {
+        Ok(()) => Ok(warp::reply::json(&rule).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save auto responder rule: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_rule_for_guild(
+    guild_id: u64,
+    rule_id: u64,
+    data: AutoResponderRuleRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut rule = match _fetch_rule(&db, guild_id, rule_id).await {
+        Ok(r) => r,
+        Err(err) => return Ok(err),
+    };
+    rule.trigger = data.trigger;
+    rule.match_kind = data.match_kind;
+    rule.case_sensitive = data.case_sensitive;
+    rule.channels = data.channels;
+    rule.content = data.content;
+    rule.embed = data.embed;
+    rule.reactions = data.reactions;
+    rule.cooldown = data.cooldown;
+    if let Err(err) = rule.validate() {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    match db.auto_responder().update_rule(&rule).await {
+        Ok(()) => Ok(warp::reply::json(&rule).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save auto responder rule: {:?}", err),
+        )),
+    }
+}
+
+pub async fn delete_rule_for_guild(
+    guild_id: u64,
+    rule_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let rule = match _fetch_rule(&db, guild_id, rule_id).await {
+        Ok(r) => r,
+        Err(err) => return Ok(err),
+    };
+
+    match db
+        .auto_responder()
+        .delete_rule(GuildId(guild_id), rule.id)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::reply().into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete auto responder rule: {:?}", err),
+        )),
+    }
+}
+
+async fn _fetch_rule(
+    db: &Database,
+    guild_id: u64,
+    rule_id: u64,
+) -> Result<AutoResponderRule, warp::reply::Response> {
+    match db
+        .auto_responder()
+        .fetch_rule(GuildId(guild_id), rule_id as i64)
+        .await
+    {
+        Ok(Some(r)) => Ok(r),
+        Ok(None) => Err(util::create_error_response(
+            StatusCode::NOT_FOUND,
+            format!("No auto responder rule with id {}", rule_id),
+        )),
+        Err(err) => Err(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch auto responder rule: {:?}", err),
+        )),
+    }
+}
@@ -0,0 +1,71 @@
+use std::{convert::Infallible, sync::Arc};
+
+use twilight_model::id::GuildId;
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{models::command_permissions::CommandPermissionDeleteRequestData, util},
+    db::Database,
+    modules::slash_commands::{permissions::CommandPermission, SlashCommandRegistry},
+};
+
+pub async fn fetch_permissions_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .command_permissions()
+        .fetch_for_guild(GuildId(guild_id))
+        .await
+    {
+        Ok(permissions) => Ok(warp::reply::json(&permissions).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch command permissions: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_permission_for_guild(
+    guild_id: u64,
+    data: CommandPermission,
+    db: Database,
+    commands: Arc<SlashCommandRegistry>,
+) -> Result<impl warp::Reply, Infallible> {
+    if !commands.command_keys().contains(&data.command) {
+        return Ok(util::create_error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown command: {}", data.command),
+        ));
+    }
+
+    match db
+        .command_permissions()
+        .upsert(GuildId(guild_id), &data)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::json(&data).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update command permission: {:?}", err),
+        )),
+    }
+}
+
+pub async fn delete_permission_for_guild(
+    guild_id: u64,
+    data: CommandPermissionDeleteRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .command_permissions()
+        .delete(GuildId(guild_id), &data.command)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::reply().into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete command permission: {:?}", err),
+        )),
+    }
+}
@@ -0,0 +1,246 @@
+use std::{convert::Infallible, sync::Arc};
+
+use twilight_http::Client;
+use twilight_model::id::GuildId;
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{
+        models::custom_commands::{CustomCommandPrefixRequestData, CustomCommandRequestData},
+        util,
+    },
+    db::Database,
+    modules::{
+        custom_commands::{self, CustomCommand},
+        slash_commands::SlashCommandRegistry,
+    },
+};
+
+pub async fn fetch_commands_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .custom_commands()
+        .fetch_commands_for_guild(GuildId(guild_id))
+        .await
+    {
+        Ok(commands) => Ok(warp::reply::json(&commands).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch custom commands: {:?}", err),
+        )),
+    }
+}
+
+pub async fn create_command_for_guild(
+    guild_id: u64,
+    data: CustomCommandRequestData,
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut command = CustomCommand {
+        id: 0,
+        guild_id: GuildId(guild_id),
+        name: data.name,
+        description: data.description,
+        content: data.content,
+        embeds: data.embeds,
+        slash: data.slash,
+        prefix: data.prefix,
+        reply_target: data.reply_target,
+        add_roles: data.add_roles,
+        remove_roles: data.remove_roles,
+        delete_trigger: data.delete_trigger,
+        allowed_channels: data.allowed_channels,
+        allowed_roles: data.allowed_roles,
+    };
+    if let Err(err) = custom_commands::validate(&command.name, &command.description, &commands) {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    if let Err(err) = db.custom_commands().create_command(&mut command).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save custom command: {:?}", err),
+        ));
+    }
+
+    if let Err(err) =
+        custom_commands::sync_guild_commands(GuildId(guild_id), &db, &client, &commands).await
+    {
+        // Discord still has the old commands, so forget the new one rather than leave a
+        // command saved that was never registered.
+        if let Err(e) = db
+            .custom_commands()
+            .delete_command(GuildId(guild_id), command.id)
+            .await
+        {
+            eprintln!(
+                "Failed to delete unregistered custom command {}: {}",
+                command.id, e
+            );
+        }
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to register custom command: {:?}", err),
+        ));
+    }
+
+    Ok(warp::reply::json(&command).into_response())
+}
+
+pub async fn update_command_for_guild(
+    guild_id: u64,
+    command_id: u64,
+    data: CustomCommandRequestData,
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut command = match _fetch_command(&db, guild_id, command_id).await {
+        Ok(c) => c,
+        Err(err) => return Ok(err),
+    };
+    let previous = command.clone();
+    command.name = data.name;
+    command.description = data.description;
+    command.content = data.content;
+    command.embeds = data.embeds;
+    command.slash = data.slash;
+    command.prefix = data.prefix;
+    command.reply_target = data.reply_target;
+    command.add_roles = data.add_roles;
+    command.remove_roles = data.remove_roles;
+    command.delete_trigger = data.delete_trigger;
+    command.allowed_channels = data.allowed_channels;
+    command.allowed_roles = data.allowed_roles;
+    if let Err(err) = custom_commands::validate(&command.name, &command.description, &commands) {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    if let Err(err) = db.custom_commands().update_command(&command).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save custom command: {:?}", err),
+        ));
+    }
+
+    if let Err(err) =
+        custom_commands::sync_guild_commands(GuildId(guild_id), &db, &client, &commands).await
+    {
+        // Discord still has the previous version registered, so put that back.
+        if let Err(e) = db.custom_commands().update_command(&previous).await {
+            eprintln!(
+                "Failed to restore custom command {} after registering failed: {}",
+                command.id, e
+            );
+        }
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to register custom command: {:?}", err),
+        ));
+    }
+
+    Ok(warp::reply::json(&command).into_response())
+}
+
+pub async fn delete_command_for_guild(
+    guild_id: u64,
+    command_id: u64,
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> Result<impl warp::Reply, Infallible> {
+    let command = match _fetch_command(&db, guild_id, command_id).await {
+        Ok(c) => c,
+        Err(err) => return Ok(err),
+    };
+
+    if let Err(err) = db
+        .custom_commands()
+        .delete_command(GuildId(guild_id), command.id)
+        .await
+    {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete custom command: {:?}", err),
+        ));
+    }
+
+    if command.slash {
+        if let Err(err) =
+            custom_commands::sync_guild_commands(GuildId(guild_id), &db, &client, &commands).await
+        {
+            return Ok(util::create_error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to unregister custom command: {:?}", err),
+            ));
+        }
+    }
+
+    Ok(warp::reply::reply().into_response())
+}
+
+pub async fn fetch_prefix_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db.custom_commands().fetch_prefix(GuildId(guild_id)).await {
+        Ok(prefix) => {
+            Ok(warp::reply::json(&CustomCommandPrefixRequestData { prefix }).into_response())
+        }
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch custom command prefix: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_prefix_for_guild(
+    guild_id: u64,
+    data: CustomCommandPrefixRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    if data.prefix.trim().is_empty() || data.prefix.chars().any(char::is_whitespace) {
+        return Ok(util::create_error_response(
+            StatusCode::BAD_REQUEST,
+            "The prefix can't be empty or contain spaces".into(),
+        ));
+    }
+
+    match db
+        .custom_commands()
+        .upsert_prefix(GuildId(guild_id), &data.prefix)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::json(&data).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update custom command prefix: {:?}", err),
+        )),
+    }
+}
+
+async fn _fetch_command(
+    db: &Database,
+    guild_id: u64,
+    command_id: u64,
+) -> Result<CustomCommand, warp::reply::Response> {
+    match db
+        .custom_commands()
+        .fetch_command(GuildId(guild_id), command_id as i64)
+        .await
+    {
+        Ok(Some(c)) => Ok(c),
+        Ok(None) => Err(util::create_error_response(
+            StatusCode::NOT_FOUND,
+            format!("No custom command with id {}", command_id),
+        )),
+        Err(err) => Err(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch custom command: {:?}", err),
+        )),
+    }
+}
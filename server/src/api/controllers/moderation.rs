@@ -0,0 +1,304 @@
+use std::convert::Infallible;
+
+use chrono::Utc;
+use twilight_model::id::{GuildId, UserId};
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{
+        models::moderation::{
+            CaseRequestData, ModerationRequestData, ModeratorRolesRequestData, NoteRequestData,
+            WarnThresholdRequestData,
+        },
+        util,
+    },
+    db::Database,
+    modules::moderation::{
+        escalation::WarnThreshold, validate_reason, ModAction, ModCase, ModerationConfig,
+        DEFAULT_DM_TEMPLATE,
+    },
+};
+
+pub async fn fetch_config_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match _fetch_config(&db, guild_id).await {
+        Ok(config) => Ok(warp::reply::json(&config).into_response()),
+        Err(err) => Ok(err),
+    }
+}
+
+pub async fn update_config_for_guild(
+    guild_id: u64,
+    data: ModerationRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut config = match _fetch_config(&db, guild_id).await {
+        Ok(c) => c,
+        Err(err) => return Ok(err),
+    };
+    config.mute_role_id = data.mute_role_id;
+    config.dm_target = data.dm_target;
+    config.dm_template = data
+        .dm_template
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_DM_TEMPLATE.into());
+    if config.dm_template.chars().count() > 2000 {
+        return Ok(util::create_error_response(
+            StatusCode::BAD_REQUEST,
+            "The DM template must be at most 2000 characters".into(),
+        ));
+    }
+
+    match db.moderation().upsert_config(&config).await {
+        Ok(()) => Ok(warp::reply::json(&config).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update moderation settings: {:?}", err),
+        )),
+    }
+}
+
+pub async fn fetch_roles_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match _fetch_config(&db, guild_id).await {
+        Ok(config) => Ok(warp::reply::json(&ModeratorRolesRequestData {
+            moderator_roles: config.moderator_roles,
+            admin_roles: config.admin_roles,
+        })
+        .into_response()),
+        Err(err) => Ok(err),
+    }
+}
+
+pub async fn update_roles_for_guild(
+    guild_id: u64,
+    data: ModeratorRolesRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut config = ModerationConfig::new(GuildId(guild_id));
+    config.moderator_roles = data.moderator_roles;
+    config.admin_roles = data.admin_roles;
+
+    match db.moderation().upsert_roles(&config).await {
+        Ok(()) => Ok(warp::reply::json(&ModeratorRolesRequestData {
+            moderator_roles: config.moderator_roles,
+            admin_roles: config.admin_roles,
+        })
+        .into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update moderator roles: {:?}", err),
+        )),
+    }
+}
+
+pub async fn fetch_cases_for_user(
+    guild_id: u64,
+    user_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .moderation()
+        .fetch_all_cases_for_user(GuildId(guild_id), UserId(user_id))
+        .await
+    {
+        Ok(cases) => Ok(warp::reply::json(&cases).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch cases: {:?}", err),
+        )),
+    }
+}
+
+pub async fn create_note_for_user(
+    guild_id: u64,
+    user_id: u64,
+    data: NoteRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let content = data.content.trim();
+    if let Err(err) = validate_reason(ModAction::Note, content) {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    let mut case = ModCase {
+        id: 0,
+        guild_id: GuildId(guild_id),
+        case_number: 0,
+        action: ModAction::Note,
+        target_id: UserId(user_id),
+        moderator_id: data.moderator_id,
+        reason: content.into(),
+        duration: None,
+        created_at: Utc::now().naive_utc(),
+        expired_at: None,
+        related_cases: vec![],
+    };
+    match db.moderation().create_case(&mut case).await {
+        Ok(()) => Ok(warp::reply::json(&case).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create note: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_case_for_guild(
+    guild_id: u64,
+    case_number: i64,
+    data: CaseRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut case = match _fetch_case(&db, guild_id, case_number).await {
+        Ok(c) => c,
+        Err(err) => return Ok(err),
+    };
+    let reason = data.reason.trim();
+    if let Err(err) = validate_reason(case.action, reason) {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    match db
+        .moderation()
+        .update_case_reason(case.guild_id, case.case_number, reason)
+        .await
+    {
+        Ok(()) => {
+            case.reason = reason.into();
+            Ok(warp::reply::json(&case).into_response())
+        }
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update case: {:?}", err),
+        )),
+    }
+}
+
+pub async fn delete_case_for_guild(
+    guild_id: u64,
+    case_number: i64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let case = match _fetch_case(&db, guild_id, case_number).await {
+        Ok(c) => c,
+        Err(err) => return Ok(err),
+    };
+
+    match db
+        .moderation()
+        .delete_case(case.guild_id, case.case_number)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::reply().into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete case: {:?}", err),
+        )),
+    }
+}
+
+pub async fn fetch_thresholds_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .moderation()
+        .fetch_thresholds_for_guild(GuildId(guild_id))
+        .await
+    {
+        Ok(thresholds) => Ok(warp::reply::json(&thresholds).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch warning thresholds: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_thresholds_for_guild(
+    guild_id: u64,
+    data: Vec<WarnThresholdRequestData>,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut thresholds = data
+        .into_iter()
+        .map(|threshold| WarnThreshold {
+            id: 0,
+            guild_id: GuildId(guild_id),
+            warnings: threshold.warnings,
+            period: threshold.period,
+            action: threshold.action,
+            duration: threshold.duration,
+        })
+        .collect::<Vec<_>>();
+    for (i, threshold) in thresholds.iter().enumerate() {
+        if let Err(err) = threshold.validate() {
+            return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+        }
+        if thresholds[..i]
+            .iter()
+            .any(|other| other.warnings == threshold.warnings)
+        {
+            return Ok(util::create_error_response(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "There's more than one threshold for {} warnings",
+                    threshold.warnings
+                ),
+            ));
+        }
+    }
+
+    match db
+        .moderation()
+        .replace_thresholds(GuildId(guild_id), &mut thresholds)
+        .await
+    {
+        Ok(()) => Ok(warp::reply::json(&thresholds).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update warning thresholds: {:?}", err),
+        )),
+    }
+}
+
+/// Notes have nothing else to them, so they can't be empty.
+async fn _fetch_case(
+    db: &Database,
+    guild_id: u64,
+    case_number: i64,
+) -> Result<ModCase, warp::reply::Response> {
+    match db
+        .moderation()
+        .fetch_case(GuildId(guild_id), case_number)
+        .await
+    {
+        Ok(Some(case)) => Ok(case),
+        Ok(None) => Err(util::create_error_response(
+            StatusCode::NOT_FOUND,
+            format!("Case #{} not found", case_number),
+        )),
+        Err(err) => Err(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch case: {:?}", err),
+        )),
+    }
+}
+
+async fn _fetch_config(
+    db: &Database,
+    guild_id: u64,
+) -> Result<ModerationConfig, warp::reply::Response> {
+    db.moderation()
+        .fetch_config(GuildId(guild_id))
+        .await
+        .map_err(|err| {
+            util::create_error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch moderation settings: {:?}", err),
+            )
+        })
+}
@@ -0,0 +1,203 @@
+use std::convert::Infallible;
+
+use twilight_http::Client;
+use twilight_model::id::{GuildId, MessageId};
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{
+        models::reaction_roles::{ReactionRolesRequestData, ReactionRolesUpdateRequestData},
+        util,
+    },
+    db::Database,
+    modules::reaction_roles::ReactionRolesMsg,
+};
+
+pub async fn fetch_messages_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db
+        .reaction_roles()
+        .fetch_messages_for_guild(GuildId(guild_id))
+        .await
+    {
+        Ok(messages) => Ok(warp::reply::json(&messages).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch reaction role messages: {:?}", err),
+        )),
+    }
+}
+
+pub async fn create_message_for_guild(
+    guild_id: u64,
+    data: ReactionRolesRequestData,
+    db: Database,
+    client: Client,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut message = ReactionRolesMsg {
+        message_id: None,
+        channel_id: data.channel_id,
+        guild_id: GuildId(guild_id),
+        content: data.content,
+        embeds: data.embeds.into_iter().map(|e| e.into()).collect(),
+        role_map: data.roles,
+        mode: data.mode,
+        limit: data.limit,
+        style: data.style,
+    };
+    if let Err(err) = message.validate() {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    if let Err(err) = message.post(&client).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to post reaction role message: {:?}", err),
+        ));
+    }
+
+    if let Err(err) = db.reaction_roles().create_message(&message).await {
+        // Nothing would handle the posted message without its row, so take it down again.
+        if let Some(message_id) = message.message_id {
+            if let Err(e) = client
+                .delete_message(message.channel_id, message_id)
+                .exec()
+                .await
+            {
+                eprintln!(
+                    "Failed to delete unsaved reaction role message {}: {}",
+                    message_id, e
+                );
+            }
+        }
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save reaction role message: {:?}", err),
+        ));
+    }
+
+    if let Err(err) = message.add_reactions(&client).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!(
+                "Posted the reaction role message but failed to add its reactions: {:?}",
+                err
+            ),
+        ));
+    }
+
+    Ok(warp::reply::json(&message).into_response())
+}
+
+pub async fn update_message_for_guild(
+    guild_id: u64,
+    message_id: u64,
+    data: ReactionRolesUpdateRequestData,
+    db: Database,
+    client: Client,
+) -> Result<impl warp::Reply, Infallible> {
+    let previous = match _fetch_message(&db, guild_id, message_id).await {
+        Ok(m) => m,
+        Err(err) => return Ok(err),
+    };
+
+    let mut message = previous.clone();
+    if let Some(content) = data.content {
+        message.content = content;
+    }
+    if let Some(embeds) = data.embeds {
+        message.embeds = embeds.into_iter().map(|e| e.into()).collect();
+    }
+    if let Some(roles) = data.roles {
+        message.role_map = roles;
+    }
+    if let Some(mode) = data.mode {
+        message.mode = mode;
+    }
+    if let Some(limit) = data.limit {
+        message.limit = limit;
+    }
+    if let Some(style) = data.style {
+        message.style = style;
+    }
+    if let Err(err) = message.validate() {
+        return Ok(util::create_error_response(StatusCode::BAD_REQUEST, err));
+    }
+
+    if let Err(err) = message.update(&previous, &client).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update reaction role message: {:?}", err),
+        ));
+    }
+
+    if let Err(err) = db.reaction_roles().update_message(&message).await {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save reaction role message: {:?}", err),
+        ));
+    }
+
+    Ok(warp::reply::json(&message).into_response())
+}
+
+pub async fn delete_message_for_guild(
+    guild_id: u64,
+    message_id: u64,
+    db: Database,
+    client: Client,
+) -> Result<impl warp::Reply, Infallible> {
+    let message = match _fetch_message(&db, guild_id, message_id).await {
+        Ok(m) => m,
+        Err(err) => return Ok(err),
+    };
+
+    // The message may already have been deleted by hand, which shouldn't stop us forgetting it.
+    if let Err(err) = client
+        .delete_message(message.channel_id, MessageId(message_id))
+        .exec()
+        .await
+    {
+        eprintln!(
+            "Failed to delete reaction role message {}: {}",
+            message_id, err
+        );
+    }
+
+    if let Err(err) = db
+        .reaction_roles()
+        .delete_message(GuildId(guild_id), MessageId(message_id))
+        .await
+    {
+        return Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete reaction role message: {:?}", err),
+        ));
+    }
+
+    Ok(warp::reply::reply().into_response())
+}
+
+async fn _fetch_message(
+    db: &Database,
+    guild_id: u64,
+    message_id: u64,
+) -> Result<ReactionRolesMsg, warp::reply::Response> {
+    match db
+        .reaction_roles()
+        .fetch_message(MessageId(message_id))
+        .await
+    {
+        Ok(Some(m)) if m.guild_id == GuildId(guild_id) => Ok(m),
+        Ok(_) => Err(util::create_error_response(
+            StatusCode::NOT_FOUND,
+            format!("No reaction role message with id {}", message_id),
+        )),
+        Err(err) => Err(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch reaction role message: {:?}", err),
+        )),
+    }
+}
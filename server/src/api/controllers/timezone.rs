@@ -0,0 +1,54 @@
+use std::convert::Infallible;
+
+use chrono_tz::Tz;
+use twilight_model::id::GuildId;
+use warp::{hyper::StatusCode, Reply};
+
+use crate::{
+    api::{models::timezone::TimezoneRequestData, util},
+    db::Database,
+};
+
+pub async fn fetch_timezone_for_guild(
+    guild_id: u64,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    match db.timezone().fetch_guild_timezone(GuildId(guild_id)).await {
+        Ok(timezone) => Ok(warp::reply::json(&TimezoneRequestData { timezone }).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch timezone: {:?}", err),
+        )),
+    }
+}
+
+pub async fn update_timezone_for_guild(
+    guild_id: u64,
+    data: TimezoneRequestData,
+    db: Database,
+) -> Result<impl warp::Reply, Infallible> {
+    let result = match &data.timezone {
+        Some(timezone) => match timezone.parse::<Tz>() {
+            Ok(timezone) => {
+                db.timezone()
+                    .upsert_guild_timezone(GuildId(guild_id), timezone.name())
+                    .await
+            }
+            Err(_) => {
+                return Ok(util::create_error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown timezone: {}", timezone),
+                ))
+            }
+        },
+        None => db.timezone().delete_guild_timezone(GuildId(guild_id)).await,
+    };
+
+    match result {
+        Ok(()) => Ok(warp::reply::json(&data).into_response()),
+        Err(err) => Ok(util::create_error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update timezone: {:?}", err),
+        )),
+    }
+}
@@ -4,15 +4,21 @@ mod recover;
 mod routes;
 mod util;
 
+use std::sync::Arc;
+
 use twilight_cache_inmemory::InMemoryCache;
 use twilight_http::Client;
 use warp::{hyper::Method, Filter};
 
-use crate::db::Database;
+use crate::{db::Database, modules::slash_commands::SlashCommandRegistry};
 
 use serde::Serialize;
 
-use self::routes::{guild::guild_routes, welcome::welcome_routes};
+use self::routes::{
+    auto_responder::auto_responder_routes, command_permissions::command_permissions_routes,
+    custom_commands::custom_commands_routes, guild::guild_routes, moderation::moderation_routes,
+    reaction_roles::reaction_roles_routes, timezone::timezone_routes, welcome::welcome_routes,
+};
 
 #[derive(Serialize)]
 struct ErrorMessage {
@@ -38,18 +44,31 @@ fn with_cache(
     warp::any().map(move || cache.clone())
 }
 
+fn with_commands(
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = (Arc<SlashCommandRegistry>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || commands.clone())
+}
+
 pub fn routes(
     db: Database,
     client: Client,
     cache: InMemoryCache,
+    commands: Arc<SlashCommandRegistry>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     let cors = warp::cors()
         .allow_origin("http://localhost:3000")
         .allow_header("content-type")
         .allow_methods(&[Method::GET, Method::POST, Method::DELETE]);
 
-    guild_routes(client, cache)
-        .or(welcome_routes(db))
+    guild_routes(client.clone(), cache)
+        .or(welcome_routes(db.clone()))
+        .or(timezone_routes(db.clone()))
+        .or(command_permissions_routes(db.clone(), commands.clone()))
+        .or(custom_commands_routes(db.clone(), client.clone(), commands))
+        .or(auto_responder_routes(db.clone()))
+        .or(moderation_routes(db.clone()))
+        .or(reaction_roles_routes(db, client))
         .recover(recover::handle_rejection)
         .with(cors)
 }
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+use twilight_model::id::ChannelId;
+
+use crate::{
+    models::{embed::Embed, emoji::EmojiKey},
+    modules::auto_responder::MatchKind,
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResponderRuleRequestData {
+    pub trigger: String,
+    pub match_kind: MatchKind,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub channels: Vec<ChannelId>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub embed: Option<Embed>,
+    #[serde(default)]
+    pub reactions: Vec<EmojiKey>,
+    #[serde(default)]
+    pub cooldown: i64,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use twilight_model::id::{ChannelId, RoleId};
+
+use crate::{models::embed::Embed, modules::custom_commands::ReplyTarget};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommandRequestData {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub embeds: Vec<Embed>,
+    #[serde(default = "default_slash")]
+    pub slash: bool,
+    #[serde(default)]
+    pub prefix: bool,
+    #[serde(default)]
+    pub reply_target: ReplyTarget,
+    #[serde(default)]
+    pub add_roles: Vec<RoleId>,
+    #[serde(default)]
+    pub remove_roles: Vec<RoleId>,
+    #[serde(default)]
+    pub delete_trigger: bool,
+    #[serde(default)]
+    pub allowed_channels: Vec<ChannelId>,
+    #[serde(default)]
+    pub allowed_roles: Vec<RoleId>,
+}
+
+fn default_slash() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommandPrefixRequestData {
+    pub prefix: String,
+}
@@ -1 +1,7 @@
+pub mod auto_responder;
+pub mod command_permissions;
+pub mod custom_commands;
+pub mod moderation;
+pub mod reaction_roles;
+pub mod timezone;
 pub mod welcome;
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{RoleId, UserId};
+
+use crate::modules::moderation::ModAction;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationRequestData {
+    pub mute_role_id: Option<RoleId>,
+    #[serde(default)]
+    pub dm_target: bool,
+    pub dm_template: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeratorRolesRequestData {
+    #[serde(default)]
+    pub moderator_roles: Vec<RoleId>,
+    #[serde(default)]
+    pub admin_roles: Vec<RoleId>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRequestData {
+    pub content: String,
+    pub moderator_id: UserId,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseRequestData {
+    pub reason: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WarnThresholdRequestData {
+    pub warnings: i64,
+    pub period: Option<i64>,
+    pub action: ModAction,
+    pub duration: Option<i64>,
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer};
+use twilight_model::id::{ChannelId, RoleId};
+
+use crate::{
+    models::{embed::Embed, emoji::EmojiKey},
+    modules::reaction_roles::{ReactionRolesMode, ReactionRolesStyle},
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionRolesRequestData {
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub embeds: Vec<Embed>,
+    pub roles: HashMap<EmojiKey, RoleId>,
+    #[serde(default)]
+    pub mode: ReactionRolesMode,
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub style: ReactionRolesStyle,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionRolesUpdateRequestData {
+    pub content: Option<String>,
+    pub embeds: Option<Vec<Embed>>,
+    pub roles: Option<HashMap<EmojiKey, RoleId>>,
+    pub mode: Option<ReactionRolesMode>,
+    pub style: Option<ReactionRolesStyle>,
+    /// `Some(None)` clears the limit, `None` leaves it unchanged.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub limit: Option<Option<u32>>,
+}
+
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Deserialize::deserialize(deserializer).map(Some)
+}
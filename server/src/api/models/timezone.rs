@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// A guild's timezone, or `None` to read times in UTC.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimezoneRequestData {
+    pub timezone: Option<String>,
+}
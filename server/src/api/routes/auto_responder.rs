@@ -0,0 +1,57 @@
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::auto_responder::{
+            create_rule_for_guild, delete_rule_for_guild, fetch_rules_for_guild,
+            update_rule_for_guild,
+        },
+        with_db,
+    },
+    db::Database,
+};
+
+pub fn auto_responder_routes(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone())
+        .or(create(db.clone()))
+        .or(update(db.clone()))
+        .or(delete(db))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "auto-responder")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_rules_for_guild)
+}
+
+fn create(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "auto-responder")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(create_rule_for_guild)
+}
+
+fn update(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "auto-responder" / u64)
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_rule_for_guild)
+}
+
+fn delete(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "auto-responder" / u64)
+        .and(warp::delete())
+        .and(with_db(db))
+        .and_then(delete_rule_for_guild)
+}
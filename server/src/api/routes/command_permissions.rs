@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::command_permissions::{
+            delete_permission_for_guild, fetch_permissions_for_guild, update_permission_for_guild,
+        },
+        with_commands, with_db,
+    },
+    db::Database,
+    modules::slash_commands::SlashCommandRegistry,
+};
+
+pub fn command_permissions_routes(
+    db: Database,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone())
+        .or(update(db.clone(), commands))
+        .or(delete(db))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "command-permissions")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_permissions_for_guild)
+}
+
+fn update(
+    db: Database,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "command-permissions")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and(with_commands(commands))
+        .and_then(update_permission_for_guild)
+}
+
+fn delete(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "command-permissions")
+        .and(warp::delete())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(delete_permission_for_guild)
+}
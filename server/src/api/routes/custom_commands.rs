@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use twilight_http::Client;
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::custom_commands::{
+            create_command_for_guild, delete_command_for_guild, fetch_commands_for_guild,
+            fetch_prefix_for_guild, update_command_for_guild, update_prefix_for_guild,
+        },
+        with_client, with_commands, with_db,
+    },
+    db::Database,
+    modules::slash_commands::SlashCommandRegistry,
+};
+
+pub fn custom_commands_routes(
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone())
+        .or(create(db.clone(), client.clone(), commands.clone()))
+        .or(update(db.clone(), client.clone(), commands.clone()))
+        .or(delete(db.clone(), client, commands))
+        .or(fetch_prefix(db.clone()))
+        .or(update_prefix(db))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_commands_for_guild)
+}
+
+fn create(
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and(with_commands(commands))
+        .and_then(create_command_for_guild)
+}
+
+fn update(
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands" / u64)
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and(with_commands(commands))
+        .and_then(update_command_for_guild)
+}
+
+fn delete(
+    db: Database,
+    client: Client,
+    commands: Arc<SlashCommandRegistry>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands" / u64)
+        .and(warp::delete())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and(with_commands(commands))
+        .and_then(delete_command_for_guild)
+}
+
+fn fetch_prefix(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands" / "prefix")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_prefix_for_guild)
+}
+
+fn update_prefix(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "custom-commands" / "prefix")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_prefix_for_guild)
+}
@@ -1,2 +1,8 @@
+pub mod auto_responder;
+pub mod command_permissions;
+pub mod custom_commands;
 pub mod guild;
+pub mod moderation;
+pub mod reaction_roles;
+pub mod timezone;
 pub mod welcome;
@@ -0,0 +1,122 @@
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::moderation::{
+            create_note_for_user, delete_case_for_guild, fetch_cases_for_user,
+            fetch_config_for_guild, fetch_roles_for_guild, fetch_thresholds_for_guild,
+            update_case_for_guild, update_config_for_guild, update_roles_for_guild,
+            update_thresholds_for_guild,
+        },
+        with_db,
+    },
+    db::Database,
+};
+
+pub fn moderation_routes(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone())
+        .or(update(db.clone()))
+        .or(fetch_roles(db.clone()))
+        .or(update_roles(db.clone()))
+        .or(fetch_cases(db.clone()))
+        .or(create_note(db.clone()))
+        .or(update_case(db.clone()))
+        .or(delete_case(db.clone()))
+        .or(fetch_thresholds(db.clone()))
+        .or(update_thresholds(db))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_config_for_guild)
+}
+
+fn update(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_config_for_guild)
+}
+
+fn fetch_roles(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "roles")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_roles_for_guild)
+}
+
+fn update_roles(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "roles")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_roles_for_guild)
+}
+
+fn fetch_cases(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "users" / u64 / "cases")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_cases_for_user)
+}
+
+fn create_note(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "users" / u64 / "notes")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(create_note_for_user)
+}
+
+fn update_case(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "cases" / i64)
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_case_for_guild)
+}
+
+fn delete_case(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "cases" / i64)
+        .and(warp::delete())
+        .and(with_db(db))
+        .and_then(delete_case_for_guild)
+}
+
+fn fetch_thresholds(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "thresholds")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_thresholds_for_guild)
+}
+
+fn update_thresholds(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "moderation" / "thresholds")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_thresholds_for_guild)
+}
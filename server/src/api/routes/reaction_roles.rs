@@ -0,0 +1,65 @@
+use twilight_http::Client;
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::reaction_roles::{
+            create_message_for_guild, delete_message_for_guild, fetch_messages_for_guild,
+            update_message_for_guild,
+        },
+        with_client, with_db,
+    },
+    db::Database,
+};
+
+pub fn reaction_roles_routes(
+    db: Database,
+    client: Client,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone())
+        .or(create(db.clone(), client.clone()))
+        .or(update(db.clone(), client.clone()))
+        .or(delete(db, client))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "reaction-roles")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_messages_for_guild)
+}
+
+fn create(
+    db: Database,
+    client: Client,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "reaction-roles")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and_then(create_message_for_guild)
+}
+
+fn update(
+    db: Database,
+    client: Client,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "reaction-roles" / u64)
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and_then(update_message_for_guild)
+}
+
+fn delete(
+    db: Database,
+    client: Client,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "reaction-roles" / u64)
+        .and(warp::delete())
+        .and(with_db(db))
+        .and(with_client(client))
+        .and_then(delete_message_for_guild)
+}
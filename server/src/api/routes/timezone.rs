@@ -0,0 +1,32 @@
+use warp::Filter;
+
+use crate::{
+    api::{
+        controllers::timezone::{fetch_timezone_for_guild, update_timezone_for_guild},
+        with_db,
+    },
+    db::Database,
+};
+
+pub fn timezone_routes(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    fetch(db.clone()).or(update(db))
+}
+
+fn fetch(db: Database) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "timezone")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(fetch_timezone_for_guild)
+}
+
+fn update(
+    db: Database,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!(u64 / "timezone")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db))
+        .and_then(update_timezone_for_guild)
+}
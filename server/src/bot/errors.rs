@@ -0,0 +1,92 @@
+use std::{error::Error, fmt::Display};
+
+use twilight_http::{
+    api_error::{ApiError, ErrorCode, GeneralApiError},
+    error::ErrorType,
+};
+
+use crate::{modules::slash_commands::SlashCommandError, util::time::TimeParseError};
+
+/// An error caused by the member using an interaction, which they should be told about.
+#[derive(Debug)]
+pub enum UserError {
+    /// The interaction was used with input that can't be acted on.
+    BadInput(String),
+    /// The member isn't allowed to do what they asked.
+    MissingPermissions(String),
+}
+
+impl Display for UserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserError::BadInput(message) | UserError::MissingPermissions(message) => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl Error for UserError {}
+
+/// What to tell a member when handling their interaction failed. Errors they can fix are
+/// explained, anything else only gets a generic message since the detail is logged.
+pub fn user_message(error: &(dyn Error + Send + Sync + 'static)) -> String {
+    if let Some(error) = error.downcast_ref::<UserError>() {
+        return error.to_string();
+    }
+    if let Some(error) = error.downcast_ref::<TimeParseError>() {
+        return error.to_string();
+    }
+    if let Some(error) = error.downcast_ref::<SlashCommandError>() {
+        return match error {
+            SlashCommandError::CannotProcessUnknownCommand => {
+                "This command isn't supported anymore".into()
+            }
+            _ => error.to_string(),
+        };
+    }
+    if let Some(error) = error.downcast_ref::<twilight_http::Error>() {
+        return match error.kind() {
+            ErrorType::Response {
+                error:
+                    ApiError::General(GeneralApiError {
+                        code: ErrorCode::MissingPermissions | ErrorCode::MissingAccess,
+                        ..
+                    }),
+                ..
+            } => "I don't have permission to do that here".into(),
+            _ => "Discord couldn't complete that, please try again later".into(),
+        };
+    }
+    if error.downcast_ref::<sqlx::Error>().is_some() {
+        return "Something went wrong saving that, please try again later".into();
+    }
+
+    "Something went wrong, please try again later".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::util::time::TimeParseError;
+
+    use super::{user_message, UserError};
+
+    #[test]
+    fn test_user_message() {
+        let error: Box<dyn Error + Send + Sync> =
+            Box::new(UserError::BadInput("There is no poll with that id".into()));
+        assert_eq!(user_message(&*error), "There is no poll with that id");
+
+        let error: Box<dyn Error + Send + Sync> =
+            Box::new(TimeParseError::InPast("yesterday".into()));
+        assert_eq!(user_message(&*error), "\"yesterday\" is in the past");
+
+        let error: Box<dyn Error + Send + Sync> = "Poll was never posted".into();
+        assert_eq!(
+            user_message(&*error),
+            "Something went wrong, please try again later"
+        );
+    }
+}
@@ -1,20 +1,27 @@
 use std::error::Error;
 
+use chrono::{Duration, Utc};
 use twilight_gateway::Event;
 use twilight_model::{
     application::{
         callback::{CallbackData, InteractionResponse},
-        interaction::{ApplicationCommand, Interaction},
+        component::Component,
+        interaction::{ApplicationCommand, Interaction, MessageComponentInteraction},
     },
-    channel::embed::Embed,
+    channel::{embed::Embed, message::MessageFlags, Message},
+    id::InteractionId,
 };
 
-use crate::modules::{
-    slash_commands,
-    welcome::{handle_member_add, handle_member_remove},
+use crate::{
+    modules::{
+        auto_responder, components, custom_commands,
+        reaction_roles::{handle_reaction_add, handle_reaction_remove},
+        welcome::{handle_member_add, handle_member_remove},
+    },
+    scheduler::{Job, GUILD_RETENTION_DAYS},
 };
 
-use super::DiscordBot;
+use super::{errors, DiscordBot};
 
 /// Utility struct to store state and services used for event handling.
 ///
@@ -35,24 +42,125 @@ impl EventHandler<'_> {
         println!("HANDLING EVENT: {:?}\n", event);
 
         match event {
+            Event::GuildCreate(guild) => {
+                let db = &handler.bot.db;
+                db.guild().validate_guilds(&[guild.0.id]).await?;
+                // The bot may have been added back before the guild's data was purged.
+                db.scheduled_jobs()
+                    .cancel_job(
+                        guild.0.id,
+                        &Job::PurgeGuild {
+                            guild_id: guild.0.id,
+                        },
+                    )
+                    .await?
+            }
+            // An unavailable guild is an outage, not the bot being removed. Otherwise the
+            // guild's data is kept for a while in case the bot is added back.
+            Event::GuildDelete(guild) if !guild.unavailable => {
+                handler
+                    .bot
+                    .db
+                    .scheduled_jobs()
+                    .create_job(
+                        guild.id,
+                        &Job::PurgeGuild { guild_id: guild.id },
+                        Utc::now().naive_utc() + Duration::days(GUILD_RETENTION_DAYS),
+                    )
+                    .await?
+            }
             Event::MemberAdd(member_add) => handle_member_add(member_add, &handler).await?,
             Event::MemberRemove(member_remove) => {
                 handle_member_remove(member_remove, &handler).await?
             }
             Event::InteractionCreate(interaction) => match interaction.0 {
                 Interaction::ApplicationCommand(command) => {
-                    slash_commands::process(&command, &handler).await?
+                    if let Err(e) = handler.bot.commands.process(&command, &handler).await {
+                        handler
+                            .interaction_error_reply(command.id, &command.token, &*e)
+                            .await;
+                        return Err(e);
+                    }
+                }
+                Interaction::MessageComponent(component) => {
+                    if let Err(e) = components::process(&component, &handler).await {
+                        handler
+                            .interaction_error_reply(component.id, &component.token, &*e)
+                            .await;
+                        return Err(e);
+                    }
                 }
                 _ => {}
             },
-            Event::ReactionAdd(_reaction) => {}
-            Event::ReactionRemove(_reaction) => {}
+            Event::MessageCreate(message) => {
+                // The modules don't depend on each other, so one failing shouldn't stop the other.
+                if let Err(e) = custom_commands::handle_message(&message, &handler).await {
+                    eprintln!(
+                        "Failed to handle custom command in message {}: {}",
+                        message.id, e
+                    );
+                }
+                if let Err(e) = auto_responder::handle_message(&message, &handler).await {
+                    eprintln!(
+                        "Failed to run auto responder on message {}: {}",
+                        message.id, e
+                    );
+                }
+            }
+            Event::ReactionAdd(reaction_add) => handle_reaction_add(reaction_add, &handler).await?,
+            Event::ReactionRemove(reaction_remove) => {
+                handle_reaction_remove(reaction_remove, &handler).await?
+            }
             _ => {}
         }
 
         Ok(())
     }
 
+    /// Tell the member their interaction failed, as an ephemeral reply or, when the
+    /// interaction was already responded to, an ephemeral follow-up.
+    ///
+    /// Failing to tell them is only logged, so the original error is what gets reported.
+    pub async fn interaction_error_reply(
+        &self,
+        id: InteractionId,
+        token: &str,
+        error: &(dyn Error + Send + Sync + 'static),
+    ) {
+        let message = errors::user_message(error);
+        let reply = self
+            .bot
+            .http
+            .interaction_callback(
+                id,
+                token,
+                &InteractionResponse::ChannelMessageWithSource(CallbackData {
+                    allowed_mentions: None,
+                    components: None,
+                    content: Some(message.clone()),
+                    embeds: vec![],
+                    flags: Some(MessageFlags::EPHEMERAL),
+                    tts: None,
+                }),
+            )
+            .exec()
+            .await;
+        if reply.is_ok() {
+            return;
+        }
+
+        let followup = match self.bot.http.create_followup_message(token) {
+            Ok(followup) => followup.content(&message).ephemeral(true).exec().await,
+            Err(e) => {
+                eprintln!("Failed to reply with error to interaction {}: {}", id, e);
+                return;
+            }
+        };
+        if let Err(e) = followup {
+            eprintln!("Failed to reply with error to interaction {}: {}", id, e);
+        }
+    }
+
     pub async fn simple_interaction_reply(
         &self,
         command: &Box<ApplicationCommand>,
@@ -78,10 +186,11 @@ impl EventHandler<'_> {
         Ok(())
     }
 
-    pub async fn embed_interaction_reply(
+    /// Reply to a command with a message only the user who used it can see.
+    pub async fn ephemeral_interaction_reply(
         &self,
         command: &Box<ApplicationCommand>,
-        embeds: Vec<Embed>,
+        message: &str,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         self.bot
             .http
@@ -91,6 +200,133 @@ impl EventHandler<'_> {
                 &InteractionResponse::ChannelMessageWithSource(CallbackData {
                     allowed_mentions: None,
                     components: None,
+                    content: Some(message.into()),
+                    embeds: vec![],
+                    flags: Some(MessageFlags::EPHEMERAL),
+                    tts: None,
+                }),
+            )
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Acknowledge a command now and reply later with [`EventHandler::update_interaction_reply`],
+    /// for commands that may take longer than the three seconds Discord waits for a reply.
+    ///
+    /// Whether the reply is ephemeral has to be decided here, it can't be changed by the update.
+    pub async fn defer_interaction_reply(
+        &self,
+        command: &Box<ApplicationCommand>,
+        ephemeral: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.bot
+            .http
+            .interaction_callback(
+                command.id,
+                &command.token,
+                &InteractionResponse::DeferredChannelMessageWithSource(CallbackData {
+                    allowed_mentions: None,
+                    components: None,
+                    content: None,
+                    embeds: vec![],
+                    flags: if ephemeral {
+                        Some(MessageFlags::EPHEMERAL)
+                    } else {
+                        None
+                    },
+                    tts: None,
+                }),
+            )
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Edit the original reply to a command, filling in a deferred reply.
+    pub async fn update_interaction_reply(
+        &self,
+        command: &Box<ApplicationCommand>,
+        content: Option<&str>,
+        embeds: &[Embed],
+        components: &[Component],
+    ) -> Result<Message, Box<dyn Error + Send + Sync>> {
+        let message = self
+            .bot
+            .http
+            .update_interaction_original(&command.token)?
+            .content(content)?
+            .embeds(Some(embeds))?
+            .components(Some(components))?
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        Ok(message)
+    }
+
+    /// Send another message in response to a command that was already replied to.
+    pub async fn followup_interaction_reply(
+        &self,
+        command: &Box<ApplicationCommand>,
+        message: &str,
+        ephemeral: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.bot
+            .http
+            .create_followup_message(&command.token)?
+            .content(message)
+            .ephemeral(ephemeral)
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reply to a component interaction with a message only the user who clicked can see.
+    pub async fn component_interaction_reply(
+        &self,
+        component: &Box<MessageComponentInteraction>,
+        message: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.bot
+            .http
+            .interaction_callback(
+                component.id,
+                &component.token,
+                &InteractionResponse::ChannelMessageWithSource(CallbackData {
+                    allowed_mentions: None,
+                    components: None,
+                    content: Some(message.into()),
+                    embeds: vec![],
+                    flags: Some(MessageFlags::EPHEMERAL),
+                    tts: None,
+                }),
+            )
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replace the message a component is on, such as to show another page.
+    pub async fn update_component_message(
+        &self,
+        component: &Box<MessageComponentInteraction>,
+        embeds: Vec<Embed>,
+        components: Vec<Component>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.bot
+            .http
+            .interaction_callback(
+                component.id,
+                &component.token,
+                &InteractionResponse::UpdateMessage(CallbackData {
+                    allowed_mentions: None,
+                    components: Some(components),
                     content: None,
                     embeds,
                     flags: None,
@@ -1,40 +1,68 @@
+pub mod errors;
 pub mod event_handler;
 
-use std::error::Error;
+use std::{error::Error, sync::Arc};
 
 use twilight_cache_inmemory::InMemoryCache;
 use twilight_gateway::{Cluster, Event};
 use twilight_http::Client;
-use twilight_model::{
-    application::command::{ChoiceCommandOptionData, Command, CommandOption, CommandType},
-    id::GuildId,
-};
+use twilight_model::id::GuildId;
 
-use crate::db::Database;
+use crate::{
+    db::Database,
+    modules::{
+        auto_responder, custom_commands, reaction_roles, slash_commands::SlashCommandRegistry,
+    },
+};
 
 use self::event_handler::EventHandler;
 
+/// Global commands can take up to an hour to propagate, so during development
+/// DEFAULT_GUILD_ID can be set to register them on a single guild instantly.
+pub fn dev_guild_id() -> Option<GuildId> {
+    dotenv::var("DEFAULT_GUILD_ID").ok().map(|id| {
+        GuildId(
+            id.parse::<u64>()
+                .expect("Failed to parse env var DEFAULT_GUILD_ID"),
+        )
+    })
+}
+
 #[derive(Clone)]
 pub struct DiscordBot {
     pub cluster: Cluster,
     pub db: Database,
     pub discord_cache: InMemoryCache,
     pub http: Client,
+    pub commands: Arc<SlashCommandRegistry>,
+    pub auto_responder_cooldowns: Arc<auto_responder::Cooldowns>,
+    pub auto_responder_regexes: Arc<auto_responder::RegexCache>,
+    pub cleared_reactions: Arc<reaction_roles::ClearedReactions>,
 }
 
 impl DiscordBot {
-    pub fn new(cluster: Cluster, db: Database, discord_cache: InMemoryCache, http: Client) -> Self {
+    pub fn new(
+        cluster: Cluster,
+        db: Database,
+        discord_cache: InMemoryCache,
+        http: Client,
+        commands: Arc<SlashCommandRegistry>,
+    ) -> Self {
         Self {
             cluster,
             db,
             discord_cache,
             http,
+            commands,
+            auto_responder_cooldowns: Arc::new(auto_responder::Cooldowns::default()),
+            auto_responder_regexes: Arc::new(auto_responder::RegexCache::default()),
+            cleared_reactions: Arc::new(reaction_roles::ClearedReactions::default()),
         }
     }
 
     pub async fn start(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         self.cluster.up().await;
-        let _guild_ids = self
+        let guild_ids = self
             .http
             .current_user_guilds()
             .exec()
@@ -44,59 +72,43 @@ impl DiscordBot {
             .iter()
             .map(|g| g.id)
             .collect::<Vec<GuildId>>();
-        // self.db.validate_guilds(guild_ids).await?;
+        self.db.guild().validate_guilds(&guild_ids).await?;
         self.set_up_global_commands().await?;
 
         Ok(())
     }
 
     async fn set_up_global_commands(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let commands = vec![
-            Command {
-                application_id: Some(self.http.application_id().unwrap()),
-                guild_id: None,
-                name: "ping".into(),
-                default_permission: None,
-                description: "Ping Bingus".into(),
-                id: None,
-                kind: CommandType::ChatInput,
-                options: vec![],
-            },
-            Command {
-                application_id: Some(self.http.application_id().unwrap()),
-                guild_id: None,
-                name: "poll".into(),
-                default_permission: None,
-                description: "Create a poll".into(),
-                id: None,
-                kind: CommandType::ChatInput,
-                options: vec![
-                    CommandOption::String(ChoiceCommandOptionData {
-                        choices: vec![],
-                        description: "The question you are polling".into(),
-                        name: "question".into(),
-                        required: true,
-                    }),
-                    CommandOption::String(ChoiceCommandOptionData {
-                        choices: vec![],
-                        description: "When do you want to stop polling?".into(),
-                        name: "ends".into(),
-                        required: false,
-                    }),
-                ],
-            },
-        ];
-        // http.set_global_commands(commands.clone()).unwrap();
-        let id = dotenv::var("DEFAULT_GUILD_ID")
-            .map(|id| {
-                id.parse::<u64>()
-                    .expect("Failed to parse env var DEFAULT_GUILD_ID")
-            })
-            .expect("env file is missing DEFAULT_GUILD_ID");
-        self.http
-            .set_guild_commands(GuildId(id), &commands)?
-            .exec()
-            .await?;
+        let commands = self
+            .commands
+            .definitions(self.http.application_id().unwrap());
+        // Guild commands are registered together, so in development the built in
+        // commands are registered alongside the dev guild's custom commands.
+        match dev_guild_id() {
+            Some(guild_id) => {
+                custom_commands::sync_guild_commands(guild_id, &self.db, &self.http, &self.commands)
+                    .await?
+            }
+            None => {
+                self.http.set_global_commands(&commands)?.exec().await?;
+            }
+        }
+        for guild_id in self
+            .db
+            .custom_commands()
+            .fetch_slash_command_guilds()
+            .await?
+        {
+            if dev_guild_id() != Some(guild_id) {
+                custom_commands::sync_guild_commands(
+                    guild_id,
+                    &self.db,
+                    &self.http,
+                    &self.commands,
+                )
+                .await?;
+            }
+        }
         Ok(())
     }
 
@@ -0,0 +1,119 @@
+use std::{convert::TryFrom, error::Error};
+
+use sqlx::SqlitePool;
+use twilight_model::id::GuildId;
+
+use crate::modules::auto_responder::{AutoResponderRule, AutoResponderRuleRow};
+
+pub struct AutoResponderQueries {
+    pool: SqlitePool,
+}
+
+impl AutoResponderQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        AutoResponderQueries { pool }
+    }
+
+    pub async fn fetch_rules_for_guild(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<AutoResponderRule>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, AutoResponderRuleRow>(
+            "SELECT * FROM auto_responder_rule WHERE guild_id = ? ORDER BY id",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(AutoResponderRule::try_from)
+        .collect()
+    }
+
+    pub async fn fetch_rule(
+        &self,
+        guild_id: GuildId,
+        id: i64,
+    ) -> Result<Option<AutoResponderRule>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, AutoResponderRuleRow>(
+            "SELECT * FROM auto_responder_rule WHERE guild_id = ? AND id = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(AutoResponderRule::try_from)
+        .transpose()
+    }
+
+    pub async fn create_rule(
+        &self,
+        rule: &mut AutoResponderRule,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let row: (i64,) = sqlx::query_as(
+            "
+            INSERT INTO auto_responder_rule (
+                guild_id, trigger, match_kind, case_sensitive, channels, content, embed,
+                reactions, cooldown
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            ",
+        )
+        .bind(rule.guild_id.to_string())
+        .bind(&rule.trigger)
+        .bind(rule.match_kind.as_str())
+        .bind(rule.case_sensitive)
+        .bind(serde_json::to_string(&rule.channels)?)
+        .bind(&rule.content)
+        .bind(rule.embed.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(serde_json::to_string(&rule.reactions)?)
+        .bind(rule.cooldown)
+        .fetch_one(&self.pool)
+        .await?;
+        rule.id = row.0;
+        Ok(())
+    }
+
+    pub async fn update_rule(
+        &self,
+        rule: &AutoResponderRule,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "
+            UPDATE auto_responder_rule
+            SET
+                trigger = ?,
+                match_kind = ?,
+                case_sensitive = ?,
+                channels = ?,
+                content = ?,
+                embed = ?,
+                reactions = ?,
+                cooldown = ?
+            WHERE guild_id = ? AND id = ?
+            ",
+        )
+        .bind(&rule.trigger)
+        .bind(rule.match_kind.as_str())
+        .bind(rule.case_sensitive)
+        .bind(serde_json::to_string(&rule.channels)?)
+        .bind(&rule.content)
+        .bind(rule.embed.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(serde_json::to_string(&rule.reactions)?)
+        .bind(rule.cooldown)
+        .bind(rule.guild_id.to_string())
+        .bind(rule.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_rule(&self, guild_id: GuildId, id: i64) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM auto_responder_rule WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::{convert::TryFrom, error::Error};
+
+use sqlx::SqlitePool;
+use twilight_model::id::GuildId;
+
+use crate::modules::slash_commands::permissions::{CommandPermission, CommandPermissionRow};
+
+pub struct CommandPermissionQueries {
+    pool: SqlitePool,
+}
+
+impl CommandPermissionQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        CommandPermissionQueries { pool }
+    }
+
+    pub async fn fetch_for_guild(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<CommandPermission>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, CommandPermissionRow>(
+            "SELECT command, permissions, roles FROM command_permission WHERE guild_id = ? ORDER BY command",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(CommandPermission::try_from)
+        .collect()
+    }
+
+    pub async fn fetch(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+    ) -> Result<Option<CommandPermission>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, CommandPermissionRow>(
+            "SELECT command, permissions, roles FROM command_permission WHERE guild_id = ? AND command = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(command)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(CommandPermission::try_from)
+        .transpose()
+    }
+
+    pub async fn upsert(
+        &self,
+        guild_id: GuildId,
+        permission: &CommandPermission,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "
+            INSERT INTO command_permission (guild_id, command, permissions, roles)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(guild_id, command)
+            DO UPDATE SET permissions=excluded.permissions, roles=excluded.roles
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(&permission.command)
+        .bind(permission.permissions as i64)
+        .bind(serde_json::to_string(&permission.roles)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, guild_id: GuildId, command: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM command_permission WHERE guild_id = ? AND command = ?")
+            .bind(guild_id.to_string())
+            .bind(command)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
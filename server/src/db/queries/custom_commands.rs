@@ -0,0 +1,188 @@
+use std::{convert::TryFrom, error::Error};
+
+use sqlx::SqlitePool;
+use twilight_model::id::GuildId;
+
+use crate::modules::custom_commands::{CustomCommand, CustomCommandRow, DEFAULT_PREFIX};
+
+pub struct CustomCommandQueries {
+    pool: SqlitePool,
+}
+
+impl CustomCommandQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        CustomCommandQueries { pool }
+    }
+
+    pub async fn fetch_prefix(&self, guild_id: GuildId) -> sqlx::Result<String> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT prefix FROM custom_commands WHERE guild_id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row
+            .map(|row| row.0)
+            .unwrap_or_else(|| DEFAULT_PREFIX.into()))
+    }
+
+    pub async fn upsert_prefix(&self, guild_id: GuildId, prefix: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "
+            INSERT INTO custom_commands (guild_id, prefix)
+            VALUES (?, ?)
+            ON CONFLICT(guild_id)
+            DO UPDATE SET prefix=excluded.prefix
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(prefix)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_commands_for_guild(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<CustomCommand>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, CustomCommandRow>(
+            "SELECT * FROM custom_command WHERE guild_id = ? ORDER BY name",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(CustomCommand::try_from)
+        .collect()
+    }
+
+    /// Ids of every guild with a custom command registered as a slash command.
+    pub async fn fetch_slash_command_guilds(
+        &self,
+    ) -> Result<Vec<GuildId>, Box<dyn Error + Send + Sync>> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT DISTINCT guild_id FROM custom_command WHERE slash = true")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| row.0.parse().map(GuildId))
+            .collect::<Result<_, _>>()?)
+    }
+
+    pub async fn fetch_command(
+        &self,
+        guild_id: GuildId,
+        id: i64,
+    ) -> Result<Option<CustomCommand>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, CustomCommandRow>(
+            "SELECT * FROM custom_command WHERE guild_id = ? AND id = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(CustomCommand::try_from)
+        .transpose()
+    }
+
+    pub async fn fetch_command_by_name(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Result<Option<CustomCommand>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, CustomCommandRow>(
+            "SELECT * FROM custom_command WHERE guild_id = ? AND name = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(CustomCommand::try_from)
+        .transpose()
+    }
+
+    pub async fn create_command(
+        &self,
+        command: &mut CustomCommand,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let row: (i64,) = sqlx::query_as(
+            "
+            INSERT INTO custom_command (
+                guild_id, name, description, content, embeds, slash, prefix, reply_target,
+                add_roles, remove_roles, delete_trigger, allowed_channels, allowed_roles
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            ",
+        )
+        .bind(command.guild_id.to_string())
+        .bind(&command.name)
+        .bind(&command.description)
+        .bind(&command.content)
+        .bind(serde_json::to_string(&command.embeds)?)
+        .bind(command.slash)
+        .bind(command.prefix)
+        .bind(serde_json::to_string(&command.reply_target)?)
+        .bind(serde_json::to_string(&command.add_roles)?)
+        .bind(serde_json::to_string(&command.remove_roles)?)
+        .bind(command.delete_trigger)
+        .bind(serde_json::to_string(&command.allowed_channels)?)
+        .bind(serde_json::to_string(&command.allowed_roles)?)
+        .fetch_one(&self.pool)
+        .await?;
+        command.id = row.0;
+        Ok(())
+    }
+
+    pub async fn update_command(
+        &self,
+        command: &CustomCommand,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "
+            UPDATE custom_command
+            SET
+                name = ?,
+                description = ?,
+                content = ?,
+                embeds = ?,
+                slash = ?,
+                prefix = ?,
+                reply_target = ?,
+                add_roles = ?,
+                remove_roles = ?,
+                delete_trigger = ?,
+                allowed_channels = ?,
+                allowed_roles = ?
+            WHERE guild_id = ? AND id = ?
+            ",
+        )
+        .bind(&command.name)
+        .bind(&command.description)
+        .bind(&command.content)
+        .bind(serde_json::to_string(&command.embeds)?)
+        .bind(command.slash)
+        .bind(command.prefix)
+        .bind(serde_json::to_string(&command.reply_target)?)
+        .bind(serde_json::to_string(&command.add_roles)?)
+        .bind(serde_json::to_string(&command.remove_roles)?)
+        .bind(command.delete_trigger)
+        .bind(serde_json::to_string(&command.allowed_channels)?)
+        .bind(serde_json::to_string(&command.allowed_roles)?)
+        .bind(command.guild_id.to_string())
+        .bind(command.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_command(&self, guild_id: GuildId, id: i64) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM custom_command WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
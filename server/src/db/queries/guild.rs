@@ -1,4 +1,111 @@
+use sqlx::SqlitePool;
+use twilight_model::id::GuildId;
+
+#[derive(sqlx::FromRow, serde::Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GuildPluginState {
     pub welcome: bool,
     pub reaction_roles: bool,
 }
+
+pub struct GuildQueries {
+    pool: SqlitePool,
+}
+
+impl GuildQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        GuildQueries { pool }
+    }
+
+    pub async fn plugin_state_fetch_by_guild_id(
+        &self,
+        guild_id: GuildId,
+    ) -> sqlx::Result<GuildPluginState> {
+        let state = sqlx::query_as::<_, GuildPluginState>(
+            "
+            SELECT welcome.enabled AS welcome, reaction_roles.enabled AS reaction_roles
+            FROM welcome
+            INNER JOIN reaction_roles ON welcome.guild_id = reaction_roles.guild_id
+            WHERE welcome.guild_id = ?
+            ",
+        )
+        .bind(guild_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state.unwrap_or_default())
+    }
+
+    /// Create the default (disabled) module rows for any guild that doesn't have them yet.
+    pub async fn validate_guilds(&self, guild_ids: &[GuildId]) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for guild_id in guild_ids {
+            sqlx::query("INSERT OR IGNORE INTO welcome (guild_id, enabled) VALUES (?, false)")
+                .bind(guild_id.to_string())
+                .execute(&mut tx)
+                .await?;
+            sqlx::query(
+                "INSERT OR IGNORE INTO reaction_roles (guild_id, enabled) VALUES (?, false)",
+            )
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Remove everything stored for a guild the bot is no longer a member of. This is run
+    /// by the scheduler a while after the bot leaves, see
+    /// [`GUILD_RETENTION_DAYS`](crate::scheduler::GUILD_RETENTION_DAYS).
+    pub async fn remove_guild(&self, guild_id: GuildId) -> sqlx::Result<()> {
+        let guild_id = guild_id.to_string();
+        let mut tx = self.pool.begin().await?;
+        for table in [
+            "welcome_join",
+            "welcome_join_dm",
+            "welcome_join_roles",
+            "welcome_leave",
+        ] {
+            sqlx::query(&format!(
+                "DELETE FROM {} WHERE welcome_id IN (SELECT id FROM welcome WHERE guild_id = ?)",
+                table
+            ))
+            .bind(&guild_id)
+            .execute(&mut tx)
+            .await?;
+        }
+        for table in ["poll_choice", "poll_vote"] {
+            sqlx::query(&format!(
+                "DELETE FROM {} WHERE poll_id IN (SELECT id FROM poll WHERE guild_id = ?)",
+                table
+            ))
+            .bind(&guild_id)
+            .execute(&mut tx)
+            .await?;
+        }
+        for table in [
+            "welcome",
+            "reaction_roles",
+            "reaction_roles_message",
+            "poll",
+            "poll_option",
+            "guild_timezone",
+            "command_permission",
+            "custom_commands",
+            "custom_command",
+            "auto_responder_rule",
+            "moderation",
+            "mod_case",
+            "scheduled_job",
+            "warn_threshold",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE guild_id = ?", table))
+                .bind(&guild_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
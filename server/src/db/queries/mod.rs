@@ -1,4 +1,10 @@
+pub mod auto_responder;
+pub mod command_permissions;
+pub mod custom_commands;
 pub mod guild;
+pub mod moderation;
 pub mod poll;
 pub mod reaction_roles;
+pub mod scheduled_jobs;
+pub mod timezone;
 pub mod welcome;
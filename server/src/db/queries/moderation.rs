@@ -0,0 +1,339 @@
+use std::{convert::TryFrom, error::Error};
+
+use chrono::{NaiveDateTime, Utc};
+use sqlx::SqlitePool;
+use twilight_model::id::{GuildId, UserId};
+
+use crate::modules::moderation::{
+    escalation::{WarnThreshold, WarnThresholdRow},
+    ModAction, ModCase, ModCaseRow, ModerationConfig, ModerationConfigRow,
+};
+
+pub struct ModerationQueries {
+    pool: SqlitePool,
+}
+
+impl ModerationQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        ModerationQueries { pool }
+    }
+
+    /// The guild's moderation settings, or the defaults if it hasn't saved any.
+    pub async fn fetch_config(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<ModerationConfig, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModerationConfigRow>("SELECT * FROM moderation WHERE guild_id = ?")
+            .bind(guild_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?
+            .map(ModerationConfig::try_from)
+            .unwrap_or_else(|| Ok(ModerationConfig::new(guild_id)))
+    }
+
+    /// Save the guild's settings other than its moderator and admin roles.
+    pub async fn upsert_config(&self, config: &ModerationConfig) -> sqlx::Result<()> {
+        sqlx::query(
+            "
+            INSERT INTO moderation (guild_id, mute_role_id, dm_target, dm_template)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(guild_id)
+            DO UPDATE SET
+                mute_role_id=excluded.mute_role_id,
+                dm_target=excluded.dm_target,
+                dm_template=excluded.dm_template
+            ",
+        )
+        .bind(config.guild_id.to_string())
+        .bind(config.mute_role_id.map(|id| id.to_string()))
+        .bind(config.dm_target)
+        .bind(&config.dm_template)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set the guild's moderator and admin roles, leaving its other settings alone.
+    pub async fn upsert_roles(
+        &self,
+        config: &ModerationConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "
+            INSERT INTO moderation (guild_id, moderator_roles, admin_roles)
+            VALUES (?, ?, ?)
+            ON CONFLICT(guild_id)
+            DO UPDATE SET
+                moderator_roles=excluded.moderator_roles,
+                admin_roles=excluded.admin_roles
+            ",
+        )
+        .bind(config.guild_id.to_string())
+        .bind(serde_json::to_string(&config.moderator_roles)?)
+        .bind(serde_json::to_string(&config.admin_roles)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Save a new case, numbering it after the guild's latest one. Deleted cases still count,
+    /// so a number always refers to the same case.
+    pub async fn create_case(
+        &self,
+        case: &mut ModCase,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let row: (i64, i64) = sqlx::query_as(
+            "
+            INSERT INTO mod_case (
+                guild_id, case_number, action, target_id, moderator_id, reason, duration,
+                created_at, related_cases
+            )
+            VALUES (
+                ?,
+                (SELECT COALESCE(MAX(case_number), 0) + 1 FROM mod_case WHERE guild_id = ?),
+                ?, ?, ?, ?, ?, ?, ?
+            )
+            RETURNING id, case_number
+            ",
+        )
+        .bind(case.guild_id.to_string())
+        .bind(case.guild_id.to_string())
+        .bind(case.action.as_str())
+        .bind(case.target_id.to_string())
+        .bind(case.moderator_id.to_string())
+        .bind(&case.reason)
+        .bind(case.duration)
+        .bind(case.created_at)
+        .bind(serde_json::to_string(&case.related_cases)?)
+        .fetch_one(&self.pool)
+        .await?;
+        case.id = row.0;
+        case.case_number = row.1;
+        Ok(())
+    }
+
+    /// A case by its id, even if it was deleted, for lifting its action when it expires.
+    pub async fn fetch_case_by_id(
+        &self,
+        id: i64,
+    ) -> Result<Option<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>("SELECT * FROM mod_case WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(ModCase::try_from)
+            .transpose()
+    }
+
+    pub async fn set_case_expired(&self, id: i64, expired_at: NaiveDateTime) -> sqlx::Result<()> {
+        sqlx::query("UPDATE mod_case SET expired_at = ? WHERE id = ?")
+            .bind(expired_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_case(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+    ) -> Result<Option<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>(
+            "SELECT * FROM mod_case WHERE guild_id = ? AND case_number = ? AND deleted_at IS NULL",
+        )
+        .bind(guild_id.to_string())
+        .bind(case_number)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(ModCase::try_from)
+        .transpose()
+    }
+
+    /// A page of the cases against a member, newest first.
+    pub async fn fetch_cases_for_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>(
+            "
+            SELECT * FROM mod_case
+            WHERE guild_id = ? AND target_id = ? AND deleted_at IS NULL
+            ORDER BY case_number DESC
+            LIMIT ? OFFSET ?
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ModCase::try_from)
+        .collect()
+    }
+
+    /// Every case against a member, newest first.
+    pub async fn fetch_all_cases_for_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>(
+            "
+            SELECT * FROM mod_case
+            WHERE guild_id = ? AND target_id = ? AND deleted_at IS NULL
+            ORDER BY case_number DESC
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ModCase::try_from)
+        .collect()
+    }
+
+    /// Every case against a member including deleted ones, newest first. Deleting a case
+    /// doesn't lift its action, so these are what's still in effect.
+    pub async fn fetch_actions_for_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>(
+            "
+            SELECT * FROM mod_case
+            WHERE guild_id = ? AND target_id = ?
+            ORDER BY case_number DESC
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ModCase::try_from)
+        .collect()
+    }
+
+    pub async fn count_cases_for_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            "
+                SELECT COUNT(*) FROM mod_case
+                WHERE guild_id = ? AND target_id = ? AND deleted_at IS NULL
+                ",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.0)
+    }
+
+    pub async fn update_case_reason(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+        reason: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query("UPDATE mod_case SET reason = ? WHERE guild_id = ? AND case_number = ?")
+            .bind(reason)
+            .bind(guild_id.to_string())
+            .bind(case_number)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Hide a case from the history. Its row is kept so the number isn't reused, and any
+    /// pending expiry still lifts its action.
+    pub async fn delete_case(&self, guild_id: GuildId, case_number: i64) -> sqlx::Result<()> {
+        sqlx::query("UPDATE mod_case SET deleted_at = ? WHERE guild_id = ? AND case_number = ?")
+            .bind(Utc::now().naive_utc())
+            .bind(guild_id.to_string())
+            .bind(case_number)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Every warning a member was given, newest first.
+    pub async fn fetch_warnings_for_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<ModCase>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ModCaseRow>(
+            "
+            SELECT * FROM mod_case
+            WHERE guild_id = ? AND target_id = ? AND action = ? AND deleted_at IS NULL
+            ORDER BY case_number DESC
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .bind(ModAction::Warn.as_str())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ModCase::try_from)
+        .collect()
+    }
+
+    pub async fn fetch_thresholds_for_guild(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<WarnThreshold>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, WarnThresholdRow>(
+            "SELECT * FROM warn_threshold WHERE guild_id = ? ORDER BY warnings",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(WarnThreshold::try_from)
+        .collect()
+    }
+
+    /// Replace the guild's warning thresholds with `thresholds`, filling in their ids.
+    pub async fn replace_thresholds(
+        &self,
+        guild_id: GuildId,
+        thresholds: &mut [WarnThreshold],
+    ) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM warn_threshold WHERE guild_id = ?")
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await?;
+        for threshold in thresholds.iter_mut() {
+            let row: (i64,) = sqlx::query_as(
+                "
+                INSERT INTO warn_threshold (guild_id, warnings, period, action, duration)
+                VALUES (?, ?, ?, ?, ?)
+                RETURNING id
+                ",
+            )
+            .bind(guild_id.to_string())
+            .bind(threshold.warnings)
+            .bind(threshold.period)
+            .bind(threshold.action.as_str())
+            .bind(threshold.duration)
+            .fetch_one(&mut tx)
+            .await?;
+            threshold.id = row.0;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -1,62 +1,205 @@
-use std::{error::Error, sync::Arc};
+use sqlx::SqlitePool;
+use twilight_model::id::{EmojiId, GuildId, MessageId, UserId};
 
-use rusqlite::{params, Connection};
-use tokio::sync::Mutex;
-use twilight_cache_inmemory::InMemoryCache;
-use twilight_model::id::{EmojiId, GuildId, UserId};
+use crate::modules::poll::{Poll, PollOption, PollRow};
 
-use crate::modules::poll::Poll;
-
-pub struct SqlPollQueries(pub Arc<Mutex<Connection>>);
+pub struct SqlPollQueries {
+    pool: SqlitePool,
+}
 
 impl SqlPollQueries {
-    pub async fn create_poll(&self, poll: &mut Poll) -> Result<(), Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        conn.execute(
-            "INSERT INTO poll (author, question, start, ends) VALUES (?1, ?2, ?3, ?4)",
-            params![poll.author.id.0, poll.question, poll.start, poll.ends],
-        )?;
-        poll.id = conn.last_insert_rowid();
+    pub fn new(pool: SqlitePool) -> Self {
+        SqlPollQueries { pool }
+    }
+
+    pub async fn create_poll(&self, poll: &mut Poll) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let row: (i64,) = sqlx::query_as(
+            "
+            INSERT INTO poll (
+                guild_id,
+                channel_id,
+                message_id,
+                author_id,
+                question,
+                start,
+                ends,
+                voting,
+                hide_results
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            ",
+        )
+        .bind(poll.guild_id.to_string())
+        .bind(poll.channel_id.to_string())
+        .bind(poll.message_id.map(|id| id.to_string()))
+        .bind(poll.author.id.to_string())
+        .bind(&poll.question)
+        .bind(poll.start)
+        .bind(poll.ends)
+        .bind(poll.voting.as_str())
+        .bind(poll.hide_results)
+        .fetch_one(&mut tx)
+        .await?;
+
+        for (position, option) in poll.options.iter().flatten().enumerate() {
+            sqlx::query(
+                "INSERT INTO poll_choice (poll_id, position, emoji, name) VALUES (?, ?, ?, ?)",
+            )
+            .bind(row.0)
+            .bind(position as i64)
+            .bind(option.emoji.to_string())
+            .bind(&option.name)
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        poll.id = row.0;
         Ok(())
     }
 
-    pub async fn fetch_polls(&self, cache: &InMemoryCache) -> Result<Vec<Poll>, Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        let polls = conn
-            .prepare("SELECT * from poll")?
-            .query_map([], |row| {
-                Ok(Poll {
-                    id: row.get(0)?,
-                    author: cache.user(UserId(row.get(1)?)).unwrap(),
-                    question: row.get(2)?,
-                    start: row.get(3)?,
-                    ends: row.get(4)?,
-                    options: None,
-                })
-            })?
-            .map(|res| res.unwrap())
-            .collect();
-        Ok(polls)
+    pub async fn fetch_poll_choices(&self, poll_id: i64) -> sqlx::Result<Vec<PollOption>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT emoji, name FROM poll_choice WHERE poll_id = ? ORDER BY position",
+        )
+        .bind(poll_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(emoji, name)| PollOption {
+                emoji: emoji.parse().unwrap(),
+                name,
+            })
+            .collect())
+    }
+
+    pub async fn fetch_polls(&self, guild_id: GuildId) -> sqlx::Result<Vec<PollRow>> {
+        sqlx::query_as::<_, PollRow>("SELECT * FROM poll WHERE guild_id = ?")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn fetch_poll(&self, id: i64) -> sqlx::Result<Option<PollRow>> {
+        sqlx::query_as::<_, PollRow>("SELECT * FROM poll WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// The guild's posted polls that haven't been closed, oldest first.
+    pub async fn fetch_open_polls(&self, guild_id: GuildId) -> sqlx::Result<Vec<PollRow>> {
+        sqlx::query_as::<_, PollRow>(
+            "SELECT * FROM poll WHERE guild_id = ? AND closed = false AND message_id IS NOT NULL ORDER BY id",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn fetch_poll_by_message_id(
+        &self,
+        message_id: MessageId,
+    ) -> sqlx::Result<Option<PollRow>> {
+        sqlx::query_as::<_, PollRow>("SELECT * FROM poll WHERE message_id = ?")
+            .bind(message_id.to_string())
+            .fetch_optional(&self.pool)
+            .await
     }
 
-    pub async fn delete_poll(&self, id: i64) -> Result<(), Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        conn.execute("DELETE poll WHERE id = ?", params![id])?;
+    /// Record a member's vote, replacing any vote they already cast on the poll.
+    pub async fn upsert_vote(
+        &self,
+        poll_id: i64,
+        user_id: UserId,
+        position: i64,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            "
+            INSERT INTO poll_vote (poll_id, user_id, position) VALUES (?, ?, ?)
+            ON CONFLICT(poll_id, user_id)
+            DO UPDATE SET
+                position=excluded.position
+            ",
+        )
+        .bind(poll_id)
+        .bind(user_id.to_string())
+        .bind(position)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The number of votes for each choice position that has any.
+    pub async fn fetch_vote_counts(&self, poll_id: i64) -> sqlx::Result<Vec<(i64, i64)>> {
+        sqlx::query_as(
+            "SELECT position, COUNT(*) FROM poll_vote WHERE poll_id = ? GROUP BY position",
+        )
+        .bind(poll_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record the message a poll was posted as.
+    pub async fn set_poll_message(&self, id: i64, message_id: MessageId) -> sqlx::Result<()> {
+        sqlx::query("UPDATE poll SET message_id = ? WHERE id = ?")
+            .bind(message_id.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn close_poll(&self, id: i64) -> sqlx::Result<()> {
+        sqlx::query("UPDATE poll SET closed = true WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a poll along with its choices and votes.
+    pub async fn delete_poll(&self, id: i64) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for table in ["poll_choice", "poll_vote"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE poll_id = ?", table))
+                .bind(id)
+                .execute(&mut tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM poll WHERE id = ?")
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
         Ok(())
     }
 
     pub async fn fetch_poll_options(
         &self,
         guild_id: GuildId,
-    ) -> Result<((EmojiId, String), (EmojiId, String)), Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        let options = conn.query_row("SELECT positive_id, positive_name, negative_id, negative_name FROM poll_option WHERE id = ?", params![guild_id.0], |row| {
-            Ok((
-                (EmojiId(row.get(0)?), row.get(1)?),
-                (EmojiId(row.get(2)?), row.get(3)?)
-            ))
-        })?;
-        Ok(options)
+    ) -> sqlx::Result<((EmojiId, String), (EmojiId, String))> {
+        let row: (String, String, String, String) = sqlx::query_as(
+            "
+            SELECT positive_id, positive_name, negative_id, negative_name
+            FROM poll_option
+            WHERE guild_id = ?
+            ",
+        )
+        .bind(guild_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let parse_id = |id: &str| {
+            id.parse::<u64>()
+                .map(EmojiId)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+        };
+        Ok(((parse_id(&row.0)?, row.1), (parse_id(&row.2)?, row.3)))
     }
 
     pub async fn upsert_poll_options(
@@ -64,26 +207,26 @@ impl SqlPollQueries {
         guild_id: GuildId,
         positive: (EmojiId, String),
         negative: (EmojiId, String),
-    ) -> Result<(), Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        conn.execute(
+    ) -> sqlx::Result<()> {
+        sqlx::query(
             "
-            INSERT INTO poll_option (id, positive_id, positive_name, negative_id, negative_name)
-            VALUES (?1 , ?2, ?3, ?4, ?5)
-            ON CONFLICT(id) DO UPDATE SET
+            INSERT INTO poll_option (guild_id, positive_id, positive_name, negative_id, negative_name)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id)
+            DO UPDATE SET
                 positive_id=excluded.positive_id,
-                positive_name=excluded.positive_name
-                negative_id=excluded.negative_id
-                negative_name=excluded.negative_name;
+                positive_name=excluded.positive_name,
+                negative_id=excluded.negative_id,
+                negative_name=excluded.negative_name
             ",
-            params![
-                guild_id.0,
-                positive.0 .0,
-                positive.1,
-                negative.0 .0,
-                negative.1
-            ],
-        )?;
+        )
+        .bind(guild_id.to_string())
+        .bind(positive.0.to_string())
+        .bind(positive.1)
+        .bind(negative.0.to_string())
+        .bind(negative.1)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 }
@@ -1,67 +1,129 @@
-use std::{error::Error, sync::Arc};
+use std::{convert::TryFrom, error::Error};
 
-use rusqlite::{params, Connection};
-use serde_json::json;
-use tokio::sync::Mutex;
-use twilight_model::id::{ChannelId, GuildId, MessageId};
+use sqlx::SqlitePool;
+use twilight_model::id::{GuildId, MessageId};
 
-use crate::modules::reaction_roles::ReactionRolesMsg;
+use crate::modules::reaction_roles::{ReactionRolesMsg, ReactionRolesMsgRow};
 
-pub struct SqlReactionRolesQueries(pub Arc<Mutex<Connection>>);
+pub struct SqlReactionRolesQueries {
+    pool: SqlitePool,
+}
 
 impl SqlReactionRolesQueries {
-    pub async fn create_message(&self, message: &ReactionRolesMsg) -> Result<(), Box<dyn Error>> {
-        if message.message_id.is_none() {
-            return Err("message_id is not set".into());
-        }
-        let conn = self.0.lock().await;
-        conn.execute(
+    pub fn new(pool: SqlitePool) -> Self {
+        SqlReactionRolesQueries { pool }
+    }
+
+    pub async fn create_message(
+        &self,
+        message: &ReactionRolesMsg,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let message_id = match message.message_id {
+            Some(id) => id,
+            None => return Err("message_id is not set".into()),
+        };
+        sqlx::query(
             "
             INSERT INTO reaction_roles_message (
-                message_id
-                channel_id
-                guild_id
-                content
-                embeds
-                roles
-            ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6
-            )
-        ",
-            params![
-                message.message_id.unwrap().0,
-                message.channel_id.0,
-                message.guild_id.0,
-                message.content,
-                json!(message.embeds),
-                json!(message.role_map)
-            ],
-        )?;
+                message_id,
+                channel_id,
+                guild_id,
+                content,
+                embeds,
+                roles,
+                mode,
+                role_limit,
+                style
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(message_id.to_string())
+        .bind(message.channel_id.to_string())
+        .bind(message.guild_id.to_string())
+        .bind(&message.content)
+        .bind(serde_json::to_string(&message.embeds)?)
+        .bind(serde_json::to_string(&message.role_map)?)
+        .bind(message.mode.as_str())
+        .bind(message.limit.map(i64::from))
+        .bind(message.style.as_str())
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
     pub async fn fetch_messages_for_guild(
         &self,
         guild_id: GuildId,
-    ) -> Result<Vec<ReactionRolesMsg>, Box<dyn Error>> {
-        let conn = self.0.lock().await;
-        let messages = conn
-            .prepare("SELECT * FROM reaction_roles_message WHERE guild_id = ?")?
-            .query_map(params![guild_id.0], |row| {
-                Ok(ReactionRolesMsg {
-                    message_id: Some(MessageId(row.get(0)?)),
-                    channel_id: ChannelId(row.get(1)?),
-                    guild_id,
-                    content: row.get(3)?,
-                    embeds: serde_json::from_value(row.get(4)?)
-                        .expect("Could not deserialize embeds"),
-                    role_map: serde_json::from_value(row.get(5)?)
-                        .expect("Could not deserialize role map"),
-                })
-            })?
-            .map(|res| res.unwrap())
-            .collect();
+    ) -> Result<Vec<ReactionRolesMsg>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query_as::<_, ReactionRolesMsgRow>(
+            "SELECT * FROM reaction_roles_message WHERE guild_id = ?",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(ReactionRolesMsg::try_from).collect()
+    }
+
+    pub async fn fetch_message(
+        &self,
+        message_id: MessageId,
+    ) -> Result<Option<ReactionRolesMsg>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query_as::<_, ReactionRolesMsgRow>(
+            "SELECT * FROM reaction_roles_message WHERE message_id = ?",
+        )
+        .bind(message_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(ReactionRolesMsg::try_from(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn update_message(
+        &self,
+        message: &ReactionRolesMsg,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let message_id = match message.message_id {
+            Some(id) => id,
+            None => return Err("message_id is not set".into()),
+        };
+        sqlx::query(
+            "
+            UPDATE reaction_roles_message SET
+                content = ?,
+                embeds = ?,
+                roles = ?,
+                mode = ?,
+                role_limit = ?,
+                style = ?
+            WHERE message_id = ?
+            ",
+        )
+        .bind(&message.content)
+        .bind(serde_json::to_string(&message.embeds)?)
+        .bind(serde_json::to_string(&message.role_map)?)
+        .bind(message.mode.as_str())
+        .bind(message.limit.map(i64::from))
+        .bind(message.style.as_str())
+        .bind(message_id.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 
-        Ok(messages)
+    pub async fn delete_message(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+    ) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM reaction_roles_message WHERE guild_id = ? AND message_id = ?")
+            .bind(guild_id.to_string())
+            .bind(message_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
     }
 }
@@ -0,0 +1,80 @@
+use std::{convert::TryFrom, error::Error};
+
+use chrono::NaiveDateTime;
+use sqlx::SqlitePool;
+use twilight_model::id::GuildId;
+
+use crate::scheduler::{Job, ScheduledJob, ScheduledJobRow};
+
+pub struct ScheduledJobQueries {
+    pool: SqlitePool,
+}
+
+impl ScheduledJobQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        ScheduledJobQueries { pool }
+    }
+
+    pub async fn create_job(
+        &self,
+        guild_id: GuildId,
+        job: &Job,
+        due_at: NaiveDateTime,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("INSERT INTO scheduled_job (guild_id, job, due_at) VALUES (?, ?, ?)")
+            .bind(guild_id.to_string())
+            .bind(serde_json::to_string(job)?)
+            .bind(due_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a guild's pending `job` before it runs.
+    pub async fn cancel_job(
+        &self,
+        guild_id: GuildId,
+        job: &Job,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM scheduled_job WHERE guild_id = ? AND job = ?")
+            .bind(guild_id.to_string())
+            .bind(serde_json::to_string(job)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Every job due at or before `now`, oldest first.
+    pub async fn fetch_due_jobs(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ScheduledJob>, Box<dyn Error + Send + Sync>> {
+        sqlx::query_as::<_, ScheduledJobRow>(
+            "SELECT * FROM scheduled_job WHERE due_at <= ? ORDER BY due_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ScheduledJob::try_from)
+        .collect()
+    }
+
+    /// Count a failed attempt at a job and put it off until `retry_at`.
+    pub async fn record_failure(&self, id: i64, retry_at: NaiveDateTime) -> sqlx::Result<()> {
+        sqlx::query("UPDATE scheduled_job SET attempts = attempts + 1, due_at = ? WHERE id = ?")
+            .bind(retry_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_job(&self, id: i64) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM scheduled_job WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use sqlx::SqlitePool;
+use twilight_model::id::{GuildId, UserId};
+
+pub struct TimezoneQueries {
+    pool: SqlitePool,
+}
+
+impl TimezoneQueries {
+    pub fn new(pool: SqlitePool) -> Self {
+        TimezoneQueries { pool }
+    }
+
+    /// The timezone to read a member's times in: their own if set, otherwise their guild's.
+    pub async fn fetch_timezone(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<Option<String>> {
+        let row: (Option<String>,) = sqlx::query_as(
+            "
+            SELECT COALESCE(
+                (SELECT timezone FROM user_timezone WHERE user_id = ?),
+                (SELECT timezone FROM guild_timezone WHERE guild_id = ?)
+            )
+            ",
+        )
+        .bind(user_id.to_string())
+        .bind(guild_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.0)
+    }
+
+    pub async fn fetch_guild_timezone(&self, guild_id: GuildId) -> sqlx::Result<Option<String>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT timezone FROM guild_timezone WHERE guild_id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(|row| row.0))
+    }
+
+    pub async fn upsert_guild_timezone(
+        &self,
+        guild_id: GuildId,
+        timezone: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            "
+            INSERT INTO guild_timezone (guild_id, timezone)
+            VALUES (?, ?)
+            ON CONFLICT(guild_id)
+            DO UPDATE SET timezone=excluded.timezone
+            ",
+        )
+        .bind(guild_id.to_string())
+        .bind(timezone)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_guild_timezone(&self, guild_id: GuildId) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM guild_timezone WHERE guild_id = ?")
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn upsert_user_timezone(&self, user_id: UserId, timezone: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            "
+            INSERT INTO user_timezone (user_id, timezone)
+            VALUES (?, ?)
+            ON CONFLICT(user_id)
+            DO UPDATE SET timezone=excluded.timezone
+            ",
+        )
+        .bind(user_id.to_string())
+        .bind(timezone)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_user_timezone(&self, user_id: UserId) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM user_timezone WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
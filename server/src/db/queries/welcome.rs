@@ -47,8 +47,8 @@ impl WelcomeQueries {
                 join_roles.enabled AS join_roles_enabled,
                 join_roles.roles AS join_roles_roles,
                 join_roles.delay AS join_roles_delay,
-                leave.enabled AS leave_enabled
-                leave.channel_id AS leave_channel_id
+                leave.enabled AS leave_enabled,
+                leave.channel_id AS leave_channel_id,
                 leave.content AS leave_content
             FROM welcome
             LEFT JOIN welcome_join "join" ON "join".welcome_id = welcome.id
@@ -5,9 +5,10 @@ mod db;
 mod event_runner;
 mod models;
 mod modules;
+mod scheduler;
 mod util;
 
-use std::error::Error;
+use std::{error::Error, sync::Arc};
 use twilight_cache_inmemory::{InMemoryCache, ResourceType};
 use twilight_gateway::{cluster::ShardScheme, Cluster};
 use twilight_http::Client as HttpClient;
@@ -17,6 +18,7 @@ use crate::{
     bot::DiscordBot,
     db::Database,
     event_runner::{run, EventRunner},
+    modules::slash_commands::SlashCommandRegistry,
 };
 
 #[tokio::main]
@@ -37,7 +39,10 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
 
     let (cluster, events) = Cluster::builder(
         token.clone(),
-        Intents::GUILD_MESSAGES | Intents::GUILD_MEMBERS,
+        Intents::GUILDS
+            | Intents::GUILD_MESSAGES
+            | Intents::GUILD_MESSAGE_REACTIONS
+            | Intents::GUILD_MEMBERS,
     )
     .shard_scheme(scheme)
     .build()
@@ -45,16 +50,22 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
 
     let db = Database::new(&db_path).await?;
 
-    let routes = api::routes(db.clone(), http.clone(), cache.clone());
+    let commands = Arc::new(SlashCommandRegistry::new());
+
+    let routes = api::routes(db.clone(), http.clone(), cache.clone(), commands.clone());
 
     let serve = warp::serve(routes).run(([127, 0, 0, 1], 3030));
 
-    let bot = DiscordBot::new(cluster, db.clone(), cache.clone(), http.clone());
+    let bot = DiscordBot::new(cluster, db.clone(), cache.clone(), http.clone(), commands);
     bot.start().await?;
 
     let event_runner = EventRunner::new(bot, cache.clone());
 
-    tokio::join!(serve, run(event_runner, events));
+    tokio::join!(
+        serve,
+        run(event_runner, events),
+        scheduler::run(db.clone(), http.clone())
+    );
 
     Ok(())
 }
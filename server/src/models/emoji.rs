@@ -0,0 +1,235 @@
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use twilight_http::request::prelude::RequestReactionType;
+use twilight_model::{channel::ReactionType, id::EmojiId};
+
+/// An emoji that is either a guild's custom emoji or a standard unicode one.
+///
+/// Serializes to a string so it can be used as a JSON map key: the id for a
+/// custom emoji, prefixed with `a:` if it's animated, or the emoji itself for unicode.
+#[derive(Clone, Debug)]
+pub enum EmojiKey {
+    /// A custom emoji. Whether it's animated only changes how it's written in messages,
+    /// so keys with the same id are equal either way.
+    Custom {
+        id: EmojiId,
+        animated: bool,
+    },
+    Unicode(String),
+}
+
+impl PartialEq for EmojiKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EmojiKey::Custom { id, .. }, EmojiKey::Custom { id: other_id, .. }) => id == other_id,
+            (EmojiKey::Unicode(name), EmojiKey::Unicode(other_name)) => name == other_name,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for EmojiKey {}
+
+impl Hash for EmojiKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            EmojiKey::Custom { id, .. } => id.hash(state),
+            EmojiKey::Unicode(name) => name.hash(state),
+        }
+    }
+}
+
+impl EmojiKey {
+    pub fn as_request_reaction(&self) -> RequestReactionType<'_> {
+        match self {
+            EmojiKey::Custom { id, .. } => RequestReactionType::Custom {
+                id: *id,
+                name: None,
+            },
+            EmojiKey::Unicode(name) => RequestReactionType::Unicode { name },
+        }
+    }
+
+    /// The emoji as it should be written in message content so Discord renders it.
+    pub fn as_message_text(&self) -> String {
+        match self {
+            EmojiKey::Custom { id, animated } => {
+                format!("<{}:emoji:{}>", if *animated { "a" } else { "" }, id)
+            }
+            EmojiKey::Unicode(name) => name.clone(),
+        }
+    }
+
+    pub fn to_reaction_type(&self) -> ReactionType {
+        match self {
+            EmojiKey::Custom { id, animated } => ReactionType::Custom {
+                animated: *animated,
+                id: *id,
+                name: None,
+            },
+            EmojiKey::Unicode(name) => ReactionType::Unicode { name: name.clone() },
+        }
+    }
+}
+
+impl From<&ReactionType> for EmojiKey {
+    fn from(reaction: &ReactionType) -> Self {
+        match reaction {
+            ReactionType::Custom { id, animated, .. } => EmojiKey::Custom {
+                id: *id,
+                animated: *animated,
+            },
+            ReactionType::Unicode { name } => EmojiKey::Unicode(name.clone()),
+        }
+    }
+}
+
+impl Display for EmojiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmojiKey::Custom {
+                id,
+                animated: false,
+            } => Display::fmt(id, f),
+            EmojiKey::Custom { id, animated: true } => write!(f, "a:{}", id),
+            EmojiKey::Unicode(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Accepts a bare custom emoji id, optionally prefixed with `a:` for an animated one, a
+/// custom emoji mention like `<:name:id>` or `<a:name:id>`, or anything else as a unicode
+/// emoji.
+impl FromStr for EmojiKey {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(id) = s.parse::<u64>() {
+            return Ok(EmojiKey::Custom {
+                id: EmojiId(id),
+                animated: false,
+            });
+        }
+        if let Some(Ok(id)) = s.strip_prefix("a:").map(str::parse::<u64>) {
+            return Ok(EmojiKey::Custom {
+                id: EmojiId(id),
+                animated: true,
+            });
+        }
+        if s.starts_with('<') && s.ends_with('>') {
+            if let Some(Ok(id)) = s[1..s.len() - 1].rsplit(':').next().map(str::parse::<u64>) {
+                return Ok(EmojiKey::Custom {
+                    id: EmojiId(id),
+                    animated: s.starts_with("<a:"),
+                });
+            }
+        }
+        Ok(EmojiKey::Unicode(s.to_string()))
+    }
+}
+
+impl Serialize for EmojiKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EmojiKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.trim().is_empty() {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Str(&s),
+                &"an emoji or emoji id",
+            ));
+        }
+        Ok(s.parse().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use twilight_model::id::{EmojiId, RoleId};
+
+    use super::EmojiKey;
+
+    #[test]
+    fn parse_emoji_key() {
+        assert!(matches!(
+            "759194715294793778".parse::<EmojiKey>().unwrap(),
+            EmojiKey::Custom {
+                id: EmojiId(759194715294793778),
+                animated: false
+            }
+        ));
+        assert!(matches!(
+            "<a:PeepoYes:759194715294793778>"
+                .parse::<EmojiKey>()
+                .unwrap(),
+            EmojiKey::Custom {
+                id: EmojiId(759194715294793778),
+                animated: true
+            }
+        ));
+        assert!(matches!(
+            "a:759194715294793778".parse::<EmojiKey>().unwrap(),
+            EmojiKey::Custom {
+                id: EmojiId(759194715294793778),
+                animated: true
+            }
+        ));
+        assert_eq!(
+            "✅".parse::<EmojiKey>().unwrap(),
+            EmojiKey::Unicode("✅".to_string())
+        );
+    }
+
+    #[test]
+    fn emoji_key_map_round_trip() {
+        let mut map = HashMap::new();
+        map.insert(
+            EmojiKey::Custom {
+                id: EmojiId(1),
+                animated: false,
+            },
+            RoleId(2),
+        );
+        map.insert(
+            EmojiKey::Custom {
+                id: EmojiId(4),
+                animated: true,
+            },
+            RoleId(5),
+        );
+        map.insert(EmojiKey::Unicode("✅".to_string()), RoleId(3));
+
+        let json = serde_json::to_string(&map).unwrap();
+        let parsed: HashMap<EmojiKey, RoleId> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, map);
+        assert!(matches!(
+            parsed.keys().find(|key| key.to_string() == "a:4"),
+            Some(EmojiKey::Custom { animated: true, .. })
+        ));
+    }
+
+    #[test]
+    fn test_as_message_text() {
+        let emoji = |animated| EmojiKey::Custom {
+            id: EmojiId(1),
+            animated,
+        };
+        assert_eq!(emoji(false).as_message_text(), "<:emoji:1>");
+        assert_eq!(emoji(true).as_message_text(), "<a:emoji:1>");
+        assert_eq!(emoji(false), emoji(true));
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    error::Error,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use twilight_http::Client;
+use twilight_model::{
+    gateway::payload::MessageCreate,
+    id::{ChannelId, GuildId},
+};
+
+use crate::{
+    bot::event_handler::EventHandler,
+    models::{embed::Embed, emoji::EmojiKey},
+    modules::welcome::{parse_embed, parse_message},
+};
+
+/// How a rule's trigger is compared with message content.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+    /// The whole message, ignoring surrounding whitespace.
+    Exact,
+    Contains,
+    StartsWith,
+    Regex,
+}
+
+impl MatchKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchKind::Exact => "exact",
+            MatchKind::Contains => "contains",
+            MatchKind::StartsWith => "startsWith",
+            MatchKind::Regex => "regex",
+        }
+    }
+}
+
+impl FromStr for MatchKind {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(MatchKind::Exact),
+            "contains" => Ok(MatchKind::Contains),
+            "startsWith" => Ok(MatchKind::StartsWith),
+            "regex" => Ok(MatchKind::Regex),
+            _ => Err(format!("Invalid auto responder match kind: {}", s).into()),
+        }
+    }
+}
+
+/// A rule that responds to messages whose content matches its trigger.
+///
+/// Placeholders in the reply are filled in the same way as welcome messages.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResponderRule {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub trigger: String,
+    pub match_kind: MatchKind,
+    pub case_sensitive: bool,
+    /// Channels the rule applies in, every channel if empty.
+    pub channels: Vec<ChannelId>,
+    pub content: String,
+    pub embed: Option<Embed>,
+    /// Emojis to react to the message with.
+    pub reactions: Vec<EmojiKey>,
+    /// Seconds before the rule can trigger again.
+    pub cooldown: i64,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct AutoResponderRuleRow {
+    pub id: i64,
+    pub guild_id: String,
+    pub trigger: String,
+    pub match_kind: String,
+    pub case_sensitive: bool,
+    pub channels: String,
+    pub content: String,
+    pub embed: Option<String>,
+    pub reactions: String,
+    pub cooldown: i64,
+}
+
+impl TryFrom<AutoResponderRuleRow> for AutoResponderRule {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: AutoResponderRuleRow) -> Result<Self, Self::Error> {
+        Ok(AutoResponderRule {
+            id: row.id,
+            guild_id: GuildId(row.guild_id.parse()?),
+            trigger: row.trigger,
+            match_kind: row.match_kind.parse()?,
+            case_sensitive: row.case_sensitive,
+            channels: serde_json::from_str(&row.channels)?,
+            content: row.content,
+            embed: row.embed.as_deref().map(serde_json::from_str).transpose()?,
+            reactions: serde_json::from_str(&row.reactions)?,
+            cooldown: row.cooldown,
+        })
+    }
+}
+
+impl AutoResponderRule {
+    /// Whether a message with `content` sent in `channel_id` triggers the rule.
+    pub fn matches(&self, channel_id: ChannelId, content: &str, regexes: &RegexCache) -> bool {
+        if !self.channels.is_empty() && !self.channels.contains(&channel_id) {
+            return false;
+        }
+        if self.match_kind == MatchKind::Regex {
+            return regexes
+                .get(self)
+                .map(|regex| regex.is_match(content))
+                .unwrap_or(false);
+        }
+
+        let (trigger, content) = if self.case_sensitive {
+            (self.trigger.clone(), content.to_string())
+        } else {
+            (self.trigger.to_lowercase(), content.to_lowercase())
+        };
+        match self.match_kind {
+            MatchKind::Exact => content.trim() == trigger.trim(),
+            MatchKind::Contains => content.contains(&trigger),
+            MatchKind::StartsWith => content.starts_with(&trigger),
+            MatchKind::Regex => unreachable!(),
+        }
+    }
+
+    /// Check the rule can be saved, returning a message for the dashboard if not.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.trigger.trim().is_empty() {
+            return Err("The trigger can't be empty".into());
+        }
+        if self.match_kind == MatchKind::Regex {
+            if let Err(err) = RegexBuilder::new(&self.trigger).build() {
+                return Err(format!("The trigger isn't a valid regex: {}", err));
+            }
+        }
+        if self.content.is_empty() && self.embed.is_none() && self.reactions.is_empty() {
+            return Err("The rule needs a reply, an embed or a reaction".into());
+        }
+        if self.content.chars().count() > 2000 {
+            return Err("Replies must be at most 2000 characters".into());
+        }
+        if self.cooldown < 0 {
+            return Err("The cooldown can't be negative".into());
+        }
+        Ok(())
+    }
+
+    async fn respond(
+        &self,
+        message: &MessageCreate,
+        http: &Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for emoji in &self.reactions {
+            http.create_reaction(message.channel_id, message.id, &emoji.as_request_reaction())
+                .exec()
+                .await?;
+        }
+
+        if self.content.is_empty() && self.embed.is_none() {
+            return Ok(());
+        }
+
+        let guild = http.guild(self.guild_id).exec().await?.model().await?;
+        let channel = http
+            .channel(message.channel_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        let content = parse_message(&self.content, &guild, &message.author, &channel);
+        let embeds = self
+            .embed
+            .iter()
+            .cloned()
+            .map(|embed| parse_embed(embed.into(), &guild, &message.author, &channel))
+            .collect::<Vec<_>>();
+
+        let mut reply = http
+            .create_message(message.channel_id)
+            .reply(message.id)
+            .embeds(&embeds)?;
+        if !content.is_empty() {
+            reply = reply.content(&content)?;
+        }
+        reply.exec().await?;
+
+        Ok(())
+    }
+}
+
+/// When each rule last triggered, so rules with a cooldown don't respond to every message.
+#[derive(Default)]
+pub struct Cooldowns(Mutex<HashMap<i64, Instant>>);
+
+impl Cooldowns {
+    /// Whether the rule is still cooling down from the last time it responded.
+    pub fn is_cooling_down(&self, rule: &AutoResponderRule) -> bool {
+        if rule.cooldown <= 0 {
+            return false;
+        }
+
+        let last_triggered = self.0.lock().expect("Cooldowns lock was poisoned");
+        matches!(
+            last_triggered.get(&rule.id),
+            Some(last) if last.elapsed() < Duration::from_secs(rule.cooldown as u64)
+        )
+    }
+
+    /// Record the rule as having responded now.
+    pub fn record(&self, rule: &AutoResponderRule) {
+        if rule.cooldown > 0 {
+            let mut last_triggered = self.0.lock().expect("Cooldowns lock was poisoned");
+            last_triggered.insert(rule.id, Instant::now());
+        }
+    }
+}
+
+/// Compiled regex triggers by rule id, so a rule's regex is only compiled again when the
+/// rule changes rather than for every message.
+#[derive(Default)]
+pub struct RegexCache(Mutex<HashMap<i64, CompiledTrigger>>);
+
+struct CompiledTrigger {
+    trigger: String,
+    case_sensitive: bool,
+    /// `None` if the trigger isn't a valid regex, so it isn't compiled again either.
+    regex: Option<Regex>,
+}
+
+impl RegexCache {
+    fn get(&self, rule: &AutoResponderRule) -> Option<Regex> {
+        let mut compiled = self.0.lock().expect("RegexCache lock was poisoned");
+        match compiled.get(&rule.id) {
+            Some(cached)
+                if cached.trigger == rule.trigger
+                    && cached.case_sensitive == rule.case_sensitive =>
+            {
+                cached.regex.clone()
+            }
+            _ => {
+                let regex = RegexBuilder::new(&rule.trigger)
+                    .case_insensitive(!rule.case_sensitive)
+                    .build()
+                    .ok();
+                compiled.insert(
+                    rule.id,
+                    CompiledTrigger {
+                        trigger: rule.trigger.clone(),
+                        case_sensitive: rule.case_sensitive,
+                        regex: regex.clone(),
+                    },
+                );
+                regex
+            }
+        }
+    }
+}
+
+/// Respond to a message with every rule of its guild that it triggers.
+///
+/// A rule that fails to respond is only logged, so the rules after it still get to, and its
+/// cooldown only starts once it has responded.
+pub async fn handle_message(
+    message: &MessageCreate,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) if !message.author.bot => guild_id,
+        _ => return Ok(()),
+    };
+
+    let rules = event_handler
+        .bot
+        .db
+        .auto_responder()
+        .fetch_rules_for_guild(guild_id)
+        .await?;
+    let regexes = &event_handler.bot.auto_responder_regexes;
+    for rule in rules
+        .iter()
+        .filter(|rule| rule.matches(message.channel_id, &message.content, regexes))
+    {
+        let cooldowns = &event_handler.bot.auto_responder_cooldowns;
+        if cooldowns.is_cooling_down(rule) {
+            continue;
+        }
+        match rule.respond(message, &event_handler.bot.http).await {
+            Ok(()) => cooldowns.record(rule),
+            Err(e) => eprintln!(
+                "Auto responder rule {} failed in guild {}: {}",
+                rule.id, guild_id, e
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::{ChannelId, GuildId};
+
+    use super::{AutoResponderRule, MatchKind, RegexCache};
+
+    fn rule(trigger: &str, match_kind: MatchKind) -> AutoResponderRule {
+        AutoResponderRule {
+            id: 1,
+            guild_id: GuildId(1),
+            trigger: trigger.into(),
+            match_kind,
+            case_sensitive: false,
+            channels: vec![],
+            content: "Hello!".into(),
+            embed: None,
+            reactions: vec![],
+            cooldown: 0,
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let channel = ChannelId(1);
+        let regexes = RegexCache::default();
+
+        assert!(rule("hello", MatchKind::Exact).matches(channel, " Hello ", &regexes));
+        assert!(!rule("hello", MatchKind::Exact).matches(channel, "hello there", &regexes));
+        assert!(rule("there", MatchKind::Contains).matches(channel, "hello there", &regexes));
+        assert!(rule("hello", MatchKind::StartsWith).matches(channel, "Hello there", &regexes));
+        assert!(!rule("there", MatchKind::StartsWith).matches(channel, "hello there", &regexes));
+        assert!(rule(r"^\d+ apples?$", MatchKind::Regex).matches(channel, "3 Apples", &regexes));
+        assert!(!rule("[", MatchKind::Regex).matches(channel, "[", &regexes));
+
+        let mut case_sensitive = rule("Hello", MatchKind::Contains);
+        case_sensitive.case_sensitive = true;
+        assert!(!case_sensitive.matches(channel, "hello", &regexes));
+
+        let mut scoped = rule("hello", MatchKind::Contains);
+        scoped.channels = vec![ChannelId(2)];
+        assert!(!scoped.matches(channel, "hello", &regexes));
+        assert!(scoped.matches(ChannelId(2), "hello", &regexes));
+    }
+}
@@ -0,0 +1,47 @@
+use std::{error::Error, fmt::Display};
+
+use twilight_model::application::interaction::MessageComponentInteraction;
+
+use crate::bot::event_handler::EventHandler;
+
+use super::{moderation::cases, poll::votes, reaction_roles};
+
+#[derive(Debug)]
+pub enum ComponentError {
+    CannotProcessUnknownComponent,
+}
+
+impl Display for ComponentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComponentError::CannotProcessUnknownComponent => {
+                f.write_str("Cannot process unknown component")
+            }
+        }
+    }
+}
+
+impl Error for ComponentError {}
+
+/// Route a message component interaction to the module that owns it.
+///
+/// Custom ids are namespaced as `<module>:<rest>`, so the module is everything before the first `:`.
+pub async fn process(
+    component: &Box<MessageComponentInteraction>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let module = component
+        .data
+        .custom_id
+        .split(':')
+        .next()
+        .unwrap_or_default();
+    match module {
+        votes::COMPONENT_PREFIX => votes::handle_component(component, event_handler).await,
+        reaction_roles::COMPONENT_PREFIX => {
+            reaction_roles::handle_component(component, event_handler).await
+        }
+        cases::COMPONENT_PREFIX => cases::handle_component(component, event_handler).await,
+        _ => Err(Box::new(ComponentError::CannotProcessUnknownComponent)),
+    }
+}
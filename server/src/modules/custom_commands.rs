@@ -0,0 +1,511 @@
+use std::{convert::TryFrom, error::Error};
+
+use serde::{Deserialize, Serialize};
+use twilight_http::{request::AuditLogReason, Client};
+use twilight_model::{
+    application::{
+        command::{ChoiceCommandOptionData, Command, CommandOption},
+        interaction::ApplicationCommand,
+    },
+    channel::Channel,
+    gateway::payload::MessageCreate,
+    guild::Guild,
+    id::{ChannelId, GuildId, RoleId, UserId},
+    user::User,
+};
+
+use crate::{
+    bot::{dev_guild_id, errors::UserError, event_handler::EventHandler},
+    db::Database,
+    models::embed::Embed,
+    modules::{
+        slash_commands::{chat_input, CommandOptions, SlashCommandError, SlashCommandRegistry},
+        welcome::{parse_embed_with_args, parse_message_with_args},
+    },
+};
+
+/// Prefix for text triggers in guilds that haven't chosen their own.
+pub const DEFAULT_PREFIX: &str = "!";
+const DEFAULT_DESCRIPTION: &str = "Custom command";
+
+/// Where a custom command's reply is sent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", content = "channelId", rename_all = "camelCase")]
+pub enum ReplyTarget {
+    /// The channel the command was used in.
+    Channel,
+    /// A direct message to the member who used the command.
+    Dm,
+    /// Always the same channel.
+    Fixed(ChannelId),
+}
+
+impl Default for ReplyTarget {
+    fn default() -> Self {
+        ReplyTarget::Channel
+    }
+}
+
+/// A guild-defined command that replies with text and/or embeds and can change the roles
+/// of whoever used it.
+///
+/// Placeholders like `{user.mention}` are filled in the same way as welcome messages, and
+/// `{args}`/`{arg1}` with the words given after the command name.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommand {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub name: String,
+    pub description: String,
+    pub content: String,
+    pub embeds: Vec<Embed>,
+    /// Register as a guild slash command.
+    pub slash: bool,
+    /// Trigger on messages starting with the guild's prefix and the command name.
+    pub prefix: bool,
+    pub reply_target: ReplyTarget,
+    /// Roles given to the member who used the command.
+    pub add_roles: Vec<RoleId>,
+    /// Roles taken from the member who used the command.
+    pub remove_roles: Vec<RoleId>,
+    /// Delete the message that triggered a prefix command.
+    pub delete_trigger: bool,
+    /// Channels the command can be used in, any channel if empty.
+    pub allowed_channels: Vec<ChannelId>,
+    /// Roles allowed to use the command, everyone if empty.
+    pub allowed_roles: Vec<RoleId>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct CustomCommandRow {
+    pub id: i64,
+    pub guild_id: String,
+    pub name: String,
+    pub description: String,
+    pub content: String,
+    pub embeds: String,
+    pub slash: bool,
+    pub prefix: bool,
+    pub reply_target: String,
+    pub add_roles: String,
+    pub remove_roles: String,
+    pub delete_trigger: bool,
+    pub allowed_channels: String,
+    pub allowed_roles: String,
+}
+
+impl TryFrom<CustomCommandRow> for CustomCommand {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: CustomCommandRow) -> Result<Self, Self::Error> {
+        Ok(CustomCommand {
+            id: row.id,
+            guild_id: GuildId(row.guild_id.parse()?),
+            name: row.name,
+            description: row.description,
+            content: row.content,
+            embeds: serde_json::from_str(&row.embeds)?,
+            slash: row.slash,
+            prefix: row.prefix,
+            reply_target: serde_json::from_str(&row.reply_target)?,
+            add_roles: serde_json::from_str(&row.add_roles)?,
+            remove_roles: serde_json::from_str(&row.remove_roles)?,
+            delete_trigger: row.delete_trigger,
+            allowed_channels: serde_json::from_str(&row.allowed_channels)?,
+            allowed_roles: serde_json::from_str(&row.allowed_roles)?,
+        })
+    }
+}
+
+impl CustomCommand {
+    /// The command as registered on its guild.
+    pub fn definition(&self) -> Command {
+        let description = if self.description.is_empty() {
+            DEFAULT_DESCRIPTION
+        } else {
+            &self.description
+        };
+        Command {
+            guild_id: Some(self.guild_id),
+            ..chat_input(
+                &self.name,
+                description,
+                vec![CommandOption::String(ChoiceCommandOptionData {
+                    choices: vec![],
+                    description: "Arguments for the command".into(),
+                    name: "args".into(),
+                    required: false,
+                })],
+            )
+        }
+    }
+
+    /// Whether the command can be used in `channel_id` by a member with `roles`.
+    pub fn allows(&self, channel_id: ChannelId, roles: &[RoleId]) -> bool {
+        (self.allowed_channels.is_empty() || self.allowed_channels.contains(&channel_id))
+            && (self.allowed_roles.is_empty()
+                || self.allowed_roles.iter().any(|role| roles.contains(role)))
+    }
+
+    /// Whether the command sends anything, rather than only changing roles.
+    pub fn has_reply(&self) -> bool {
+        !self.content.is_empty() || !self.embeds.is_empty()
+    }
+
+    /// The reply to send, with placeholders filled in for `user` using the command in `channel`
+    /// with `args`.
+    pub fn render(
+        &self,
+        guild: &Guild,
+        user: &User,
+        channel: &Channel,
+        args: &[String],
+    ) -> (String, Vec<twilight_model::channel::embed::Embed>) {
+        let content = parse_message_with_args(&self.content, guild, user, channel, args);
+        let embeds = self
+            .embeds
+            .iter()
+            .cloned()
+            .map(|embed| parse_embed_with_args(embed.into(), guild, user, channel, args))
+            .collect();
+        (content, embeds)
+    }
+
+    /// Give and take the command's roles from the member who used it.
+    async fn apply_roles(
+        &self,
+        user_id: UserId,
+        http: &Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let reason = format!("Custom command {}", self.name);
+        for role_id in &self.add_roles {
+            http.add_guild_member_role(self.guild_id, user_id, *role_id)
+                .reason(&reason)?
+                .exec()
+                .await?;
+        }
+        for role_id in &self.remove_roles {
+            http.remove_guild_member_role(self.guild_id, user_id, *role_id)
+                .reason(&reason)?
+                .exec()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The channel the reply goes to when the command is used by `user_id` in `channel_id`.
+    async fn reply_channel(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        http: &Client,
+    ) -> Result<ChannelId, Box<dyn Error + Send + Sync>> {
+        Ok(match self.reply_target {
+            ReplyTarget::Channel => channel_id,
+            ReplyTarget::Dm => {
+                http.create_private_channel(user_id)
+                    .exec()
+                    .await?
+                    .model()
+                    .await?
+                    .id
+            }
+            ReplyTarget::Fixed(channel_id) => channel_id,
+        })
+    }
+}
+
+async fn send_reply(
+    channel_id: ChannelId,
+    content: &str,
+    embeds: &[twilight_model::channel::embed::Embed],
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut reply = http.create_message(channel_id).embeds(embeds)?;
+    if !content.is_empty() {
+        reply = reply.content(content)?;
+    }
+    reply.exec().await?;
+
+    Ok(())
+}
+
+/// Split the text after a command name into the words used for `{argN}` placeholders.
+///
+/// A zero width space goes after every `@`, so arguments can't make the bot ping
+/// `@everyone`, a role or a member that the command itself doesn't mention.
+fn split_args(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|arg| arg.replace('@', "@\u{200B}"))
+        .collect()
+}
+
+/// Check a custom command's name and description can be registered with Discord and
+/// don't clash with a built in command.
+pub fn validate(
+    name: &str,
+    description: &str,
+    commands: &SlashCommandRegistry,
+) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > 32 {
+        return Err("Command names must be between 1 and 32 characters".into());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err("Command names may only contain lowercase letters, numbers, - and _".into());
+    }
+    if description.chars().count() > 100 {
+        return Err("Command descriptions must be at most 100 characters".into());
+    }
+    if commands.command_keys().iter().any(|key| key == name) {
+        return Err(format!("/{} is already a built in command", name));
+    }
+    Ok(())
+}
+
+/// Register a guild's custom slash commands, replacing whatever was registered before.
+///
+/// The development guild also gets the built in commands, see [`dev_guild_id`].
+pub async fn sync_guild_commands(
+    guild_id: GuildId,
+    db: &Database,
+    http: &Client,
+    commands: &SlashCommandRegistry,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let application_id = http.application_id().ok_or("Application id is not set")?;
+
+    let mut definitions = db
+        .custom_commands()
+        .fetch_commands_for_guild(guild_id)
+        .await?
+        .iter()
+        .filter(|command| command.slash)
+        .map(|command| Command {
+            application_id: Some(application_id),
+            ..command.definition()
+        })
+        .collect::<Vec<Command>>();
+    if dev_guild_id() == Some(guild_id) {
+        definitions.extend(commands.definitions(application_id));
+    }
+
+    http.set_guild_commands(guild_id, &definitions)?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Reply to a custom command used as a slash command.
+pub async fn handle_command(
+    command: &Box<ApplicationCommand>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = command
+        .guild_id
+        .ok_or(SlashCommandError::CannotProcessUnknownCommand)?;
+    let custom_command = event_handler
+        .bot
+        .db
+        .custom_commands()
+        .fetch_command_by_name(guild_id, &command.data.name)
+        .await?
+        .filter(|custom_command| custom_command.slash)
+        .ok_or(SlashCommandError::CannotProcessUnknownCommand)?;
+    let member = command
+        .member
+        .as_ref()
+        .ok_or("Custom command has no member")?;
+    let user = member.user.as_ref().ok_or("Custom command has no user")?;
+    if !custom_command.allows(command.channel_id, &member.roles) {
+        return Err(UserError::MissingPermissions(format!(
+            "You can't use /{} here",
+            custom_command.name
+        ))
+        .into());
+    }
+    let args = CommandOptions::new(&command.data.options)
+        .string("args")
+        .map(split_args)
+        .unwrap_or_default();
+
+    // Only a reply in the channel is public, anything else just gets a confirmation.
+    let reply_in_channel =
+        custom_command.has_reply() && custom_command.reply_target == ReplyTarget::Channel;
+    event_handler
+        .defer_interaction_reply(command, !reply_in_channel)
+        .await?;
+
+    let http = &event_handler.bot.http;
+    let guild = http.guild(guild_id).exec().await?.model().await?;
+    let channel = http
+        .channel(command.channel_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    custom_command.apply_roles(user.id, http).await?;
+
+    let (content, embeds) = custom_command.render(&guild, user, &channel, &args);
+    if reply_in_channel {
+        event_handler
+            .update_interaction_reply(
+                command,
+                if content.is_empty() {
+                    None
+                } else {
+                    Some(&content)
+                },
+                &embeds,
+                &[],
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let confirmation = if custom_command.has_reply() {
+        let channel_id = custom_command
+            .reply_channel(command.channel_id, user.id, http)
+            .await?;
+        send_reply(channel_id, &content, &embeds, http).await?;
+        match custom_command.reply_target {
+            ReplyTarget::Fixed(channel_id) => format!("Posted in <#{}>", channel_id),
+            _ => "Sent you a DM".into(),
+        }
+    } else {
+        "Done!".into()
+    };
+    event_handler
+        .update_interaction_reply(command, Some(&confirmation), &[], &[])
+        .await?;
+
+    Ok(())
+}
+
+/// Reply to messages starting with the guild's prefix and the name of a custom command.
+pub async fn handle_message(
+    message: &MessageCreate,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) if !message.author.bot => guild_id,
+        _ => return Ok(()),
+    };
+
+    let db = &event_handler.bot.db;
+    let prefix = db.custom_commands().fetch_prefix(guild_id).await?;
+    let (name, args) = match message.content.strip_prefix(prefix.as_str()) {
+        Some(rest) => match rest.split_whitespace().next() {
+            Some(name) => (
+                name.to_lowercase(),
+                split_args(&rest.trim_start()[name.len()..]),
+            ),
+            None => return Ok(()),
+        },
+        None => return Ok(()),
+    };
+
+    let custom_command = match db
+        .custom_commands()
+        .fetch_command_by_name(guild_id, &name)
+        .await?
+    {
+        Some(custom_command) if custom_command.prefix => custom_command,
+        _ => return Ok(()),
+    };
+    let roles = message
+        .member
+        .as_ref()
+        .map(|member| member.roles.as_slice())
+        .unwrap_or_default();
+    if !custom_command.allows(message.channel_id, roles) {
+        return Ok(());
+    }
+
+    let http = &event_handler.bot.http;
+    let guild = http.guild(guild_id).exec().await?.model().await?;
+    let channel = http
+        .channel(message.channel_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    custom_command.apply_roles(message.author.id, http).await?;
+
+    if custom_command.has_reply() {
+        let (content, embeds) = custom_command.render(&guild, &message.author, &channel, &args);
+        let channel_id = custom_command
+            .reply_channel(message.channel_id, message.author.id, http)
+            .await?;
+        send_reply(channel_id, &content, &embeds, http).await?;
+    }
+
+    if custom_command.delete_trigger {
+        http.delete_message(message.channel_id, message.id)
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::{ChannelId, GuildId, RoleId};
+
+    use super::{split_args, validate, CustomCommand, ReplyTarget};
+    use crate::modules::slash_commands::SlashCommandRegistry;
+
+    #[test]
+    fn test_validate() {
+        let commands = SlashCommandRegistry::new();
+        assert!(validate("rules", "Show the server rules", &commands).is_ok());
+        assert!(validate("faq-2", "", &commands).is_ok());
+        assert!(validate("", "", &commands).is_err());
+        assert!(validate("Rules", "", &commands).is_err());
+        assert!(validate("server rules", "", &commands).is_err());
+        assert!(validate("poll", "", &commands).is_err());
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split_args("  red   blue "), vec!["red", "blue"]);
+        assert_eq!(
+            split_args("@everyone <@&123>"),
+            vec!["@\u{200B}everyone", "<@\u{200B}&123>"]
+        );
+    }
+
+    #[test]
+    fn test_allows() {
+        let mut command = CustomCommand {
+            id: 1,
+            guild_id: GuildId(1),
+            name: "rules".into(),
+            description: String::new(),
+            content: "Be nice".into(),
+            embeds: vec![],
+            slash: true,
+            prefix: false,
+            reply_target: ReplyTarget::Channel,
+            add_roles: vec![],
+            remove_roles: vec![],
+            delete_trigger: false,
+            allowed_channels: vec![],
+            allowed_roles: vec![],
+        };
+        assert!(command.allows(ChannelId(1), &[]));
+
+        command.allowed_channels = vec![ChannelId(2)];
+        command.allowed_roles = vec![RoleId(3)];
+        assert!(command.allows(ChannelId(2), &[RoleId(4), RoleId(3)]));
+        assert!(!command.allows(ChannelId(1), &[RoleId(3)]));
+        assert!(!command.allows(ChannelId(2), &[RoleId(4)]));
+    }
+}
@@ -1,5 +1,10 @@
+pub mod components;
 pub mod slash_commands;
 
+pub mod auto_responder;
+pub mod custom_commands;
+pub mod moderation;
 pub mod poll;
 pub mod reaction_roles;
+pub mod timezone;
 pub mod welcome;
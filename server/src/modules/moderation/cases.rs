@@ -0,0 +1,158 @@
+use std::error::Error;
+
+use chrono::Duration;
+use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder};
+use twilight_model::{
+    application::{
+        component::{button::ButtonStyle, ActionRow, Button, Component},
+        interaction::MessageComponentInteraction,
+    },
+    channel::embed::Embed,
+    guild::Permissions,
+    id::{GuildId, UserId},
+};
+
+use crate::{bot::event_handler::EventHandler, db::Database, util::time::format_duration};
+
+use super::{is_moderator, ModCase};
+
+/// Module name that every case history component's custom id starts with.
+pub const COMPONENT_PREFIX: &str = "cases";
+const PAGE_CUSTOM_ID_PREFIX: &str = "cases:page:";
+const CASES_PER_PAGE: i64 = 5;
+
+/// How many pages `total` cases take up. An empty history still has a page to say so.
+fn page_count(total: i64) -> i64 {
+    ((total + CASES_PER_PAGE - 1) / CASES_PER_PAGE).max(1)
+}
+
+fn history_embed(
+    user_id: UserId,
+    cases: &[ModCase],
+    page: i64,
+    total: i64,
+) -> Result<Embed, Box<dyn Error + Send + Sync>> {
+    let mut embed = EmbedBuilder::new()
+        .title("Case history")
+        .description(format!(
+            "<@{}> has {} case{}",
+            user_id,
+            total,
+            if total == 1 { "" } else { "s" }
+        ))
+        .color(0xFFC0CB)
+        .footer(
+            EmbedFooterBuilder::new(format!("Page {} of {}", page + 1, page_count(total))).build(),
+        );
+    for case in cases {
+        let mut value = format!(
+            "{}\nBy <@{}> <t:{}:R>",
+            if case.reason.is_empty() {
+                "No reason given"
+            } else {
+                &case.reason
+            },
+            case.moderator_id,
+            case.created_at.timestamp()
+        );
+        if let Some(duration) = case.duration {
+            value += &format!(" for {}", format_duration(Duration::seconds(duration)));
+        }
+        embed = embed.field(
+            EmbedFieldBuilder::new(
+                format!("#{} | {}", case.case_number, case.action.label()),
+                value,
+            )
+            .build(),
+        );
+    }
+
+    Ok(embed.build()?)
+}
+
+fn page_button(user_id: UserId, page: i64, label: &str, disabled: bool) -> Component {
+    Component::Button(Button {
+        custom_id: Some(format!("{}{}:{}", PAGE_CUSTOM_ID_PREFIX, user_id, page)),
+        disabled,
+        emoji: None,
+        label: Some(label.into()),
+        style: ButtonStyle::Secondary,
+        url: None,
+    })
+}
+
+/// One page of the cases against a member, newest first, with buttons to move between
+/// pages if there's more than one.
+pub async fn history_page(
+    db: &Database,
+    guild_id: GuildId,
+    user_id: UserId,
+    page: i64,
+) -> Result<(Embed, Vec<Component>), Box<dyn Error + Send + Sync>> {
+    let total = db
+        .moderation()
+        .count_cases_for_user(guild_id, user_id)
+        .await?;
+    let pages = page_count(total);
+    let page = page.clamp(0, pages - 1);
+    let cases = db
+        .moderation()
+        .fetch_cases_for_user(guild_id, user_id, CASES_PER_PAGE, page * CASES_PER_PAGE)
+        .await?;
+
+    let components = if pages > 1 {
+        vec![Component::ActionRow(ActionRow {
+            components: vec![
+                page_button(user_id, page - 1, "Previous", page == 0),
+                page_button(user_id, page + 1, "Next", page == pages - 1),
+            ],
+        })]
+    } else {
+        vec![]
+    };
+
+    Ok((history_embed(user_id, &cases, page, total)?, components))
+}
+
+/// Show another page of a case history when its buttons are clicked.
+pub async fn handle_component(
+    component: &Box<MessageComponentInteraction>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (user_id, page) = component
+        .data
+        .custom_id
+        .strip_prefix(PAGE_CUSTOM_ID_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(user_id, page)| Some((UserId(user_id.parse().ok()?), page.parse().ok()?)))
+        .ok_or_else(|| format!("Unknown cases component: {}", component.data.custom_id))?;
+    let (guild_id, member) = match (component.guild_id, &component.member) {
+        (Some(guild_id), Some(member)) => (guild_id, member),
+        _ => return Err("Case history component used outside a guild".into()),
+    };
+
+    let db = &event_handler.bot.db;
+    if !is_moderator(db, guild_id, member, Permissions::MANAGE_MESSAGES).await? {
+        return event_handler
+            .component_interaction_reply(component, "Only moderators can see case history")
+            .await;
+    }
+
+    let (embed, components) = history_page(db, guild_id, user_id, page).await?;
+    event_handler
+        .update_component_message(component, vec![embed], components)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::page_count;
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0), 1);
+        assert_eq!(page_count(5), 1);
+        assert_eq!(page_count(6), 2);
+        assert_eq!(page_count(11), 3);
+    }
+}
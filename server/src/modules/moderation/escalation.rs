@@ -0,0 +1,237 @@
+use std::{convert::TryFrom, error::Error};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::Serialize;
+use twilight_http::Client;
+use twilight_model::{id::GuildId, user::User};
+
+use crate::{db::Database, util::time::format_duration};
+
+use super::{take_action, ModAction, ModCase, ModRequest, MAX_DURATION};
+
+/// A rule that takes action against a member once they've been warned enough times,
+/// like "3 warnings in 7 days: mute for an hour".
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WarnThreshold {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub warnings: i64,
+    /// Seconds the warnings have to fall within, or every warning counts if not set.
+    pub period: Option<i64>,
+    pub action: ModAction,
+    /// How long a tempban or mute lasts in seconds.
+    pub duration: Option<i64>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct WarnThresholdRow {
+    pub id: i64,
+    pub guild_id: String,
+    pub warnings: i64,
+    pub period: Option<i64>,
+    pub action: String,
+    pub duration: Option<i64>,
+}
+
+impl TryFrom<WarnThresholdRow> for WarnThreshold {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: WarnThresholdRow) -> Result<Self, Self::Error> {
+        Ok(WarnThreshold {
+            id: row.id,
+            guild_id: GuildId(row.guild_id.parse()?),
+            warnings: row.warnings,
+            period: row.period,
+            action: row.action.parse()?,
+            duration: row.duration,
+        })
+    }
+}
+
+impl WarnThreshold {
+    /// Check the threshold can be saved, returning a message for the dashboard if not.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.warnings < 1 {
+            return Err("A threshold needs at least one warning".into());
+        }
+        if matches!(self.period, Some(period) if period <= 0) {
+            return Err("The period has to be longer than zero".into());
+        }
+        match self.action {
+            ModAction::Warn | ModAction::Note => {
+                return Err("Thresholds can only kick, ban or mute".into())
+            }
+            ModAction::Tempban if self.duration.is_none() => {
+                return Err("Temporary bans need a duration".into())
+            }
+            ModAction::Kick | ModAction::Ban if self.duration.is_some() => {
+                return Err(format!("A {} can't have a duration", self.action.as_str()))
+            }
+            _ => {}
+        }
+        if matches!(self.duration, Some(duration) if duration <= 0) {
+            return Err("The duration has to be longer than zero".into());
+        }
+        if matches!(self.duration, Some(duration) if duration > MAX_DURATION) {
+            return Err(format!(
+                "The duration can be at most {}",
+                format_duration(Duration::seconds(MAX_DURATION))
+            ));
+        }
+        Ok(())
+    }
+
+    /// The reason recorded on the case the threshold escalates to.
+    fn reason(&self) -> String {
+        match self.period {
+            Some(period) => format!(
+                "Reached {} warnings in {}",
+                self.warnings,
+                format_duration(Duration::seconds(period))
+            ),
+            None => format!("Reached {} warnings", self.warnings),
+        }
+    }
+}
+
+/// The threshold a member's warnings have just reached, along with the numbers of the
+/// warnings that reached it, oldest first.
+///
+/// `warnings` are the member's warnings newest first. A threshold is reached when the
+/// warnings within its period come to exactly its count, so it escalates once on the way
+/// up rather than again with every warning after. If several are reached at once, the one
+/// needing the most warnings wins.
+fn reached<'a>(
+    thresholds: &'a [WarnThreshold],
+    warnings: &[ModCase],
+    now: NaiveDateTime,
+) -> Option<(&'a WarnThreshold, Vec<i64>)> {
+    thresholds
+        .iter()
+        .filter_map(|threshold| {
+            let counted = warnings
+                .iter()
+                .take_while(|warning| match threshold.period {
+                    Some(period) => warning.created_at > now - Duration::seconds(period),
+                    None => true,
+                })
+                .map(|warning| warning.case_number)
+                .collect::<Vec<_>>();
+            if counted.len() as i64 == threshold.warnings {
+                Some((threshold, counted.into_iter().rev().collect()))
+            } else {
+                None
+            }
+        })
+        .max_by_key(|(threshold, _)| threshold.warnings)
+}
+
+/// Escalate a warning that brings its target to one of the guild's thresholds, taking the
+/// threshold's action as the bot and recording it as a case that refers to the warnings.
+pub async fn escalate(
+    warning: &ModCase,
+    target: User,
+    db: &Database,
+    http: &Client,
+) -> Result<Option<ModCase>, Box<dyn Error + Send + Sync>> {
+    let thresholds = db
+        .moderation()
+        .fetch_thresholds_for_guild(warning.guild_id)
+        .await?;
+    if thresholds.is_empty() {
+        return Ok(None);
+    }
+
+    let warnings = db
+        .moderation()
+        .fetch_warnings_for_user(warning.guild_id, warning.target_id)
+        .await?;
+    let (threshold, related_cases) = match reached(&thresholds, &warnings, Utc::now().naive_utc()) {
+        Some(reached) => reached,
+        None => return Ok(None),
+    };
+
+    let current_user = http.current_user().exec().await?.model().await?;
+    let moderator = http.user(current_user.id).exec().await?.model().await?;
+    let case = take_action(
+        ModRequest {
+            action: threshold.action,
+            guild_id: warning.guild_id,
+            target,
+            moderator,
+            reason: threshold.reason(),
+            duration: threshold.duration.map(Duration::seconds),
+        },
+        related_cases,
+        db,
+        http,
+    )
+    .await?;
+
+    Ok(Some(case))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use twilight_model::id::{GuildId, UserId};
+
+    use super::{reached, WarnThreshold};
+    use crate::modules::moderation::{ModAction, ModCase};
+
+    fn threshold(warnings: i64, period: Option<Duration>, action: ModAction) -> WarnThreshold {
+        WarnThreshold {
+            id: warnings,
+            guild_id: GuildId(1),
+            warnings,
+            period: period.map(|period| period.num_seconds()),
+            action,
+            duration: None,
+        }
+    }
+
+    #[test]
+    fn test_reached() {
+        let now = NaiveDate::from_ymd(2021, 9, 1).and_hms(12, 0, 0);
+        let warning = |case_number, days_ago| ModCase {
+            id: case_number,
+            guild_id: GuildId(1),
+            case_number,
+            action: ModAction::Warn,
+            target_id: UserId(2),
+            moderator_id: UserId(3),
+            reason: "Spam".into(),
+            duration: None,
+            created_at: now - Duration::days(days_ago),
+            expired_at: None,
+            related_cases: vec![],
+        };
+        let thresholds = vec![
+            threshold(3, Some(Duration::days(7)), ModAction::Mute),
+            threshold(5, None, ModAction::Kick),
+        ];
+
+        let warnings = vec![warning(9, 0), warning(6, 2), warning(4, 10)];
+        assert!(reached(&thresholds, &warnings, now).is_none());
+
+        let warnings = vec![warning(9, 0), warning(6, 2), warning(4, 5)];
+        let (threshold, related) = reached(&thresholds, &warnings, now).unwrap();
+        assert_eq!(threshold.action, ModAction::Mute);
+        assert_eq!(related, vec![4, 6, 9]);
+
+        let warnings = vec![
+            warning(9, 0),
+            warning(8, 1),
+            warning(7, 2),
+            warning(2, 20),
+            warning(1, 30),
+        ];
+        let (threshold, related) = reached(&thresholds, &warnings, now).unwrap();
+        assert_eq!(threshold.action, ModAction::Kick);
+        assert_eq!(related, vec![1, 2, 7, 8, 9]);
+
+        let warnings = vec![warning(9, 0), warning(8, 1), warning(7, 2), warning(6, 3)];
+        assert!(reached(&thresholds, &warnings, now).is_none());
+    }
+}
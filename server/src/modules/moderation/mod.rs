@@ -0,0 +1,834 @@
+pub mod cases;
+pub mod escalation;
+pub mod slash_commands;
+
+use std::{convert::TryFrom, error::Error, str::FromStr};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder};
+use twilight_http::{
+    api_error::{ApiError, ErrorCode, GeneralApiError},
+    error::ErrorType,
+    request::AuditLogReason,
+    Client,
+};
+use twilight_model::{
+    channel::{embed::Embed, Channel},
+    guild::{Guild, PartialMember, Permissions, Role},
+    id::{GuildId, RoleId, UserId},
+    user::User,
+};
+
+use crate::{
+    bot::errors::UserError, db::Database, modules::welcome::parse_message, scheduler::Job,
+    util::time::format_duration,
+};
+
+/// What the target of a moderation action is sent when the guild hasn't written its own.
+pub const DEFAULT_DM_TEMPLATE: &str = "You were {action} in {server.name}. Reason: {reason}";
+/// Audit log reasons are limited to 512 characters, and also name the moderator.
+pub const MAX_REASON_LENGTH: usize = 400;
+/// The longest a tempban or mute can last, in seconds. A year is plenty, and keeps expiry
+/// times well within what dates can represent.
+pub const MAX_DURATION: i64 = 365 * 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModAction {
+    Warn,
+    Kick,
+    Ban,
+    /// A ban that lasts for the case's duration.
+    Tempban,
+    /// Giving the guild's mute role, for the case's duration if it has one.
+    Mute,
+    /// A note for other moderators, which doesn't do anything to the member.
+    Note,
+}
+
+impl ModAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModAction::Warn => "warn",
+            ModAction::Kick => "kick",
+            ModAction::Ban => "ban",
+            ModAction::Tempban => "tempban",
+            ModAction::Mute => "mute",
+            ModAction::Note => "note",
+        }
+    }
+
+    /// The action as it reads in "You were ...".
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ModAction::Warn => "warned",
+            ModAction::Kick => "kicked",
+            ModAction::Ban => "banned",
+            ModAction::Tempban => "temporarily banned",
+            ModAction::Mute => "muted",
+            ModAction::Note => "noted",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModAction::Warn => "Warning",
+            ModAction::Kick => "Kick",
+            ModAction::Ban => "Ban",
+            ModAction::Tempban => "Temporary ban",
+            ModAction::Mute => "Mute",
+            ModAction::Note => "Note",
+        }
+    }
+
+    /// The actions that replace this one when taken against the same member later, so
+    /// lifting this one would undo them too.
+    fn replaced_by(&self) -> &'static [ModAction] {
+        match self {
+            ModAction::Tempban => &[ModAction::Ban, ModAction::Tempban],
+            ModAction::Mute => &[ModAction::Mute],
+            ModAction::Warn | ModAction::Kick | ModAction::Ban | ModAction::Note => &[],
+        }
+    }
+}
+
+impl FromStr for ModAction {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(ModAction::Warn),
+            "kick" => Ok(ModAction::Kick),
+            "ban" => Ok(ModAction::Ban),
+            "tempban" => Ok(ModAction::Tempban),
+            "mute" => Ok(ModAction::Mute),
+            "note" => Ok(ModAction::Note),
+            _ => Err(format!("Invalid moderation action: {}", s).into()),
+        }
+    }
+}
+
+/// A guild's moderation settings.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationConfig {
+    pub guild_id: GuildId,
+    /// The role given by `/mute`, which the guild's channel overwrites should deny sending in.
+    pub mute_role_id: Option<RoleId>,
+    /// DM the target of an action using `dm_template`.
+    pub dm_target: bool,
+    pub dm_template: String,
+    /// Roles allowed to moderate members and manage other members' polls.
+    pub moderator_roles: Vec<RoleId>,
+    /// Roles allowed to change the bot's settings, which are moderators too.
+    pub admin_roles: Vec<RoleId>,
+}
+
+impl ModerationConfig {
+    pub fn new(guild_id: GuildId) -> Self {
+        Self {
+            guild_id,
+            mute_role_id: None,
+            dm_target: false,
+            dm_template: DEFAULT_DM_TEMPLATE.into(),
+            moderator_roles: vec![],
+            admin_roles: vec![],
+        }
+    }
+
+    /// Whether a member with `permissions` and `roles` is one of the guild's admins.
+    /// Members who can manage the guild always are.
+    pub fn is_admin(&self, permissions: Permissions, roles: &[RoleId]) -> bool {
+        permissions.intersects(Permissions::ADMINISTRATOR | Permissions::MANAGE_GUILD)
+            || self.admin_roles.iter().any(|role| roles.contains(role))
+    }
+
+    /// Whether a member with `permissions` and `roles` is one of the guild's moderators.
+    ///
+    /// Until the guild chooses moderator roles, members with the `fallback` permissions
+    /// count as moderators so the commands work out of the box.
+    pub fn is_moderator(
+        &self,
+        permissions: Permissions,
+        roles: &[RoleId],
+        fallback: Permissions,
+    ) -> bool {
+        if self.is_admin(permissions, roles) {
+            return true;
+        }
+        if self.moderator_roles.is_empty() {
+            permissions.contains(fallback)
+        } else {
+            self.moderator_roles.iter().any(|role| roles.contains(role))
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ModerationConfigRow {
+    pub guild_id: String,
+    pub mute_role_id: Option<String>,
+    pub dm_target: bool,
+    pub dm_template: String,
+    pub moderator_roles: String,
+    pub admin_roles: String,
+}
+
+impl TryFrom<ModerationConfigRow> for ModerationConfig {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: ModerationConfigRow) -> Result<Self, Self::Error> {
+        Ok(ModerationConfig {
+            guild_id: GuildId(row.guild_id.parse()?),
+            mute_role_id: row
+                .mute_role_id
+                .map(|id| id.parse().map(RoleId))
+                .transpose()?,
+            dm_target: row.dm_target,
+            dm_template: row.dm_template,
+            moderator_roles: serde_json::from_str(&row.moderator_roles)?,
+            admin_roles: serde_json::from_str(&row.admin_roles)?,
+        })
+    }
+}
+
+/// Whether the member using an interaction is one of the guild's moderators, see
+/// [`ModerationConfig::is_moderator`].
+pub async fn is_moderator(
+    db: &Database,
+    guild_id: GuildId,
+    member: &PartialMember,
+    fallback: Permissions,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(db.moderation().fetch_config(guild_id).await?.is_moderator(
+        member.permissions.unwrap_or_else(Permissions::empty),
+        &member.roles,
+        fallback,
+    ))
+}
+
+/// Whether the member using an interaction is one of the guild's admins.
+pub async fn is_admin(
+    db: &Database,
+    guild_id: GuildId,
+    member: &PartialMember,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(db.moderation().fetch_config(guild_id).await?.is_admin(
+        member.permissions.unwrap_or_else(Permissions::empty),
+        &member.roles,
+    ))
+}
+
+/// Refuse to let a moderator act against the guild owner, the bot, or a member whose
+/// highest role is at or above their own, as Discord itself would. The owner can act
+/// against anyone else.
+pub async fn check_hierarchy(
+    action: ModAction,
+    guild_id: GuildId,
+    moderator_id: UserId,
+    moderator_roles: &[RoleId],
+    target_id: UserId,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild = http.guild(guild_id).exec().await?.model().await?;
+    if target_id == guild.owner_id {
+        return Err(UserError::MissingPermissions(format!(
+            "You can't {} the server owner",
+            action.as_str()
+        ))
+        .into());
+    }
+    let current_user = http.current_user().exec().await?.model().await?;
+    if target_id == current_user.id {
+        return Err(UserError::BadInput(format!("I can't {} myself", action.as_str())).into());
+    }
+    if moderator_id == guild.owner_id {
+        return Ok(());
+    }
+
+    let target_roles = member_roles(guild_id, target_id, http)
+        .await?
+        .unwrap_or_default();
+    if top_role_position(&guild.roles, &target_roles)
+        >= top_role_position(&guild.roles, moderator_roles)
+    {
+        return Err(UserError::MissingPermissions(format!(
+            "You can only {} members whose highest role is below yours",
+            action.as_str()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// The roles of a guild member, or `None` if the user isn't in the guild.
+async fn member_roles(
+    guild_id: GuildId,
+    user_id: UserId,
+    http: &Client,
+) -> Result<Option<Vec<RoleId>>, Box<dyn Error + Send + Sync>> {
+    match http.guild_member(guild_id, user_id).exec().await {
+        Ok(response) => Ok(Some(response.model().await?.roles)),
+        Err(e) if is_unknown_target(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The position of the highest of `member_roles` among the guild's `roles`, which is 0,
+/// the position of `@everyone`, for members without any.
+fn top_role_position(roles: &[Role], member_roles: &[RoleId]) -> i64 {
+    roles
+        .iter()
+        .filter(|role| member_roles.contains(&role.id))
+        .map(|role| role.position)
+        .max()
+        .unwrap_or(0)
+}
+
+/// A moderation action taken against a member, numbered per guild.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModCase {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub case_number: i64,
+    pub action: ModAction,
+    pub target_id: UserId,
+    pub moderator_id: UserId,
+    pub reason: String,
+    /// How long the action lasts in seconds, if it's temporary.
+    pub duration: Option<i64>,
+    pub created_at: NaiveDateTime,
+    /// When a temporary action was lifted by the scheduler.
+    pub expired_at: Option<NaiveDateTime>,
+    /// Numbers of the cases that led to this one, such as the warnings behind an escalation.
+    pub related_cases: Vec<i64>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ModCaseRow {
+    pub id: i64,
+    pub guild_id: String,
+    pub case_number: i64,
+    pub action: String,
+    pub target_id: String,
+    pub moderator_id: String,
+    pub reason: String,
+    pub duration: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub expired_at: Option<NaiveDateTime>,
+    pub related_cases: String,
+}
+
+impl TryFrom<ModCaseRow> for ModCase {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: ModCaseRow) -> Result<Self, Self::Error> {
+        Ok(ModCase {
+            id: row.id,
+            guild_id: GuildId(row.guild_id.parse()?),
+            case_number: row.case_number,
+            action: row.action.parse()?,
+            target_id: UserId(row.target_id.parse()?),
+            moderator_id: UserId(row.moderator_id.parse()?),
+            reason: row.reason,
+            duration: row.duration,
+            created_at: row.created_at,
+            expired_at: row.expired_at,
+            related_cases: serde_json::from_str(&row.related_cases)?,
+        })
+    }
+}
+
+impl ModCase {
+    /// When a temporary action is due to be lifted.
+    pub fn expires_at(&self) -> Option<NaiveDateTime> {
+        self.duration.and_then(|duration| {
+            self.created_at
+                .checked_add_signed(Duration::seconds(duration))
+        })
+    }
+
+    /// Whether a later ban or mute among `cases`, the cases against the same member, is
+    /// still in effect and relies on this case's action not being lifted.
+    fn is_replaced(&self, cases: &[ModCase]) -> bool {
+        cases.iter().any(|case| {
+            case.id > self.id
+                && case.expired_at.is_none()
+                && self.action.replaced_by().contains(&case.action)
+        })
+    }
+
+    /// The case as posted in reply to the command that created it.
+    pub fn embed(&self) -> Result<Embed, Box<dyn Error + Send + Sync>> {
+        let mut embed = EmbedBuilder::new()
+            .title(format!(
+                "Case #{} | {}",
+                self.case_number,
+                self.action.label()
+            ))
+            .color(0xFFC0CB)
+            .field(
+                EmbedFieldBuilder::new("User", format!("<@{}>", self.target_id))
+                    .inline()
+                    .build(),
+            )
+            .field(
+                EmbedFieldBuilder::new("Moderator", format!("<@{}>", self.moderator_id))
+                    .inline()
+                    .build(),
+            );
+        if let Some(duration) = self.duration {
+            embed = embed.field(
+                EmbedFieldBuilder::new("Duration", format_duration(Duration::seconds(duration)))
+                    .inline()
+                    .build(),
+            );
+        }
+        if let Some(expired_at) = self.expired_at {
+            embed = embed.field(
+                EmbedFieldBuilder::new("Expired", format!("<t:{}:R>", expired_at.timestamp()))
+                    .inline()
+                    .build(),
+            );
+        } else if let Some(expires_at) = self.expires_at() {
+            embed = embed.field(
+                EmbedFieldBuilder::new("Expires", format!("<t:{}:R>", expires_at.timestamp()))
+                    .inline()
+                    .build(),
+            );
+        }
+        if !self.related_cases.is_empty() {
+            embed = embed.field(
+                EmbedFieldBuilder::new(
+                    "Related cases",
+                    self.related_cases
+                        .iter()
+                        .map(|number| format!("#{}", number))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+                .build(),
+            );
+        }
+        Ok(embed
+            .field(
+                EmbedFieldBuilder::new(
+                    "Reason",
+                    if self.reason.is_empty() {
+                        "No reason given"
+                    } else {
+                        &self.reason
+                    },
+                )
+                .build(),
+            )
+            .field(
+                EmbedFieldBuilder::new("Date", format!("<t:{}:f>", self.created_at.timestamp()))
+                    .build(),
+            )
+            .build()?)
+    }
+}
+
+/// Check `reason` can be recorded on a case with `action`, returning a message for the
+/// moderator if not. A note is nothing but its reason, so it can't be empty.
+pub fn validate_reason(action: ModAction, reason: &str) -> Result<(), String> {
+    if action == ModAction::Note && reason.is_empty() {
+        return Err("Notes can't be empty".into());
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(format!(
+            "Reasons must be at most {} characters",
+            MAX_REASON_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// A moderation action to take, see [`execute`].
+pub struct ModRequest {
+    pub action: ModAction,
+    pub guild_id: GuildId,
+    pub target: User,
+    pub moderator: User,
+    pub reason: String,
+    pub duration: Option<Duration>,
+}
+
+/// Take a moderation action and record it as the guild's next case, returning that case
+/// followed by the case for any escalation a warning triggered.
+///
+/// Failing to escalate is only logged, since the warning itself was still given.
+pub async fn execute(
+    request: ModRequest,
+    db: &Database,
+    http: &Client,
+) -> Result<Vec<ModCase>, Box<dyn Error + Send + Sync>> {
+    let target = request.target.clone();
+    let case = take_action(request, vec![], db, http).await?;
+    if case.action != ModAction::Warn {
+        return Ok(vec![case]);
+    }
+
+    match escalation::escalate(&case, target, db, http).await {
+        Ok(Some(escalation)) => Ok(vec![case, escalation]),
+        Ok(None) => Ok(vec![case]),
+        Err(e) => {
+            eprintln!(
+                "Failed to escalate case #{} in guild {}: {}",
+                case.case_number, case.guild_id, e
+            );
+            Ok(vec![case])
+        }
+    }
+}
+
+/// Take a moderation action and record it as the guild's next case, noting the numbers
+/// of the cases that led to it.
+///
+/// If the guild wants targets DMed, kicks and bans are sent first since afterwards the bot
+/// may no longer be able to reach them, but only once it's checked the bot is able to take
+/// the action. Anything else is sent after it's been done. Failing to DM doesn't stop the
+/// action. Notes are only recorded, never sent.
+async fn take_action(
+    request: ModRequest,
+    related_cases: Vec<i64>,
+    db: &Database,
+    http: &Client,
+) -> Result<ModCase, Box<dyn Error + Send + Sync>> {
+    if matches!(request.duration, Some(duration) if duration.num_seconds() > MAX_DURATION) {
+        return Err(UserError::BadInput(format!(
+            "Durations can be at most {}",
+            format_duration(Duration::seconds(MAX_DURATION))
+        ))
+        .into());
+    }
+
+    let config = db.moderation().fetch_config(request.guild_id).await?;
+    if request.action == ModAction::Mute && config.mute_role_id.is_none() {
+        return Err(UserError::BadInput(
+            "Choose a mute role in the dashboard before muting members".into(),
+        )
+        .into());
+    }
+
+    let notify = config.dm_target && request.action != ModAction::Note;
+    let notify_first = notify
+        && matches!(
+            request.action,
+            ModAction::Kick | ModAction::Ban | ModAction::Tempban
+        );
+    if notify_first {
+        check_bot_can_remove(&request, http).await?;
+        notify_target(&request, &config.dm_template, http).await;
+    }
+
+    let audit_reason = format!(
+        "{}#{}: {}",
+        request.moderator.name,
+        request.moderator.discriminator,
+        if request.reason.is_empty() {
+            "No reason given"
+        } else {
+            &request.reason
+        }
+    );
+    match request.action {
+        ModAction::Warn | ModAction::Note => {}
+        ModAction::Kick => {
+            http.remove_guild_member(request.guild_id, request.target.id)
+                .reason(&audit_reason)?
+                .exec()
+                .await?;
+        }
+        ModAction::Ban | ModAction::Tempban => {
+            http.create_ban(request.guild_id, request.target.id)
+                .reason(&audit_reason)?
+                .exec()
+                .await?;
+        }
+        ModAction::Mute => {
+            let mute_role_id = config.mute_role_id.ok_or("Mute role is not set")?;
+            http.add_guild_member_role(request.guild_id, request.target.id, mute_role_id)
+                .reason(&audit_reason)?
+                .exec()
+                .await?;
+        }
+    }
+
+    if notify && !notify_first {
+        notify_target(&request, &config.dm_template, http).await;
+    }
+
+    let mut case = ModCase {
+        id: 0,
+        guild_id: request.guild_id,
+        case_number: 0,
+        action: request.action,
+        target_id: request.target.id,
+        moderator_id: request.moderator.id,
+        reason: request.reason,
+        duration: request.duration.map(|duration| duration.num_seconds()),
+        created_at: Utc::now().naive_utc(),
+        expired_at: None,
+        related_cases,
+    };
+    db.moderation().create_case(&mut case).await?;
+
+    if let Some(expires_at) = case.expires_at() {
+        db.scheduled_jobs()
+            .create_job(
+                case.guild_id,
+                &Job::ExpireCase { case_id: case.id },
+                expires_at,
+            )
+            .await?;
+    }
+
+    Ok(case)
+}
+
+/// Lift a temporary ban or mute whose duration has passed and mark its case expired.
+///
+/// Cases that already expired are skipped, while deleted ones are still lifted since
+/// deleting a case only hides it from the history. If a later ban or mute against the
+/// member is still in effect, as when a tempban was followed by a permanent ban, the case
+/// is marked expired without lifting anything.
+pub async fn expire_case(
+    case_id: i64,
+    db: &Database,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let case = match db.moderation().fetch_case_by_id(case_id).await? {
+        Some(case) if case.expired_at.is_none() => case,
+        _ => return Ok(()),
+    };
+
+    let cases = db
+        .moderation()
+        .fetch_actions_for_user(case.guild_id, case.target_id)
+        .await?;
+    let audit_reason = format!("Case #{} expired", case.case_number);
+    match case.action {
+        _ if case.is_replaced(&cases) => {}
+        ModAction::Tempban => {
+            let result = http
+                .delete_ban(case.guild_id, case.target_id)
+                .reason(&audit_reason)?
+                .exec()
+                .await;
+            if let Err(e) = result {
+                if !is_unknown_target(&e) {
+                    return Err(e.into());
+                }
+            }
+        }
+        ModAction::Mute => {
+            let config = db.moderation().fetch_config(case.guild_id).await?;
+            if let Some(mute_role_id) = config.mute_role_id {
+                let result = http
+                    .remove_guild_member_role(case.guild_id, case.target_id, mute_role_id)
+                    .reason(&audit_reason)?
+                    .exec()
+                    .await;
+                if let Err(e) = result {
+                    if !is_unknown_target(&e) {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        ModAction::Warn | ModAction::Kick | ModAction::Ban | ModAction::Note => {}
+    }
+
+    db.moderation()
+        .set_case_expired(case.id, Utc::now().naive_utc())
+        .await?;
+
+    Ok(())
+}
+
+/// Whether a request failed because the ban or member it's about doesn't exist, as when a
+/// member was unbanned by hand or has left the guild.
+fn is_unknown_target(error: &twilight_http::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorType::Response {
+            error: ApiError::General(GeneralApiError {
+                code: ErrorCode::UnknownBan | ErrorCode::UnknownMember,
+                ..
+            }),
+            ..
+        }
+    )
+}
+
+/// Check the bot can kick or ban the target before they're told it happened. It needs the
+/// permission and a highest role above theirs, and for a kick they have to be in the guild.
+async fn check_bot_can_remove(
+    request: &ModRequest,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild = http.guild(request.guild_id).exec().await?.model().await?;
+    let current_user = http.current_user().exec().await?.model().await?;
+    let bot_roles = member_roles(request.guild_id, current_user.id, http)
+        .await?
+        .unwrap_or_default();
+    let target_roles = member_roles(request.guild_id, request.target.id, http).await?;
+
+    let required = match request.action {
+        ModAction::Kick => Permissions::KICK_MEMBERS,
+        _ => Permissions::BAN_MEMBERS,
+    };
+    let permissions = guild
+        .roles
+        .iter()
+        .filter(|role| role.id.0 == guild.id.0 || bot_roles.contains(&role.id))
+        .fold(Permissions::empty(), |permissions, role| {
+            permissions | role.permissions
+        });
+    if !permissions.intersects(Permissions::ADMINISTRATOR | required) {
+        return Err(UserError::BadInput(format!(
+            "I don't have permission to {} members here",
+            request.action.as_str()
+        ))
+        .into());
+    }
+
+    match target_roles {
+        None if request.action == ModAction::Kick => {
+            Err(UserError::BadInput("That user isn't a member of this server".into()).into())
+        }
+        Some(target_roles)
+            if request.target.id == guild.owner_id
+                || top_role_position(&guild.roles, &target_roles)
+                    >= top_role_position(&guild.roles, &bot_roles) =>
+        {
+            Err(UserError::BadInput(format!(
+                "My highest role has to be above theirs to {} them",
+                request.action.as_str()
+            ))
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// DM the target about the action, only logging if they can't be reached.
+async fn notify_target(request: &ModRequest, template: &str, http: &Client) {
+    if let Err(e) = send_dm(request, template, http).await {
+        eprintln!(
+            "Failed to DM {} about being {}: {}",
+            request.target.id,
+            request.action.past_tense(),
+            e
+        );
+    }
+}
+
+async fn send_dm(
+    request: &ModRequest,
+    template: &str,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild = http.guild(request.guild_id).exec().await?.model().await?;
+    let channel = Channel::Private(
+        http.create_private_channel(request.target.id)
+            .exec()
+            .await?
+            .model()
+            .await?,
+    );
+
+    let content = render_dm(template, request, &guild, &channel);
+    http.create_message(channel.id())
+        .content(&content)?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Fill in a DM template's `{action}`, `{duration}` and `{reason}` along with the
+/// placeholders welcome messages have.
+fn render_dm(template: &str, request: &ModRequest, guild: &Guild, channel: &Channel) -> String {
+    // The reason goes in last so placeholders typed by the moderator are left alone.
+    parse_message(template, guild, &request.target, channel)
+        .replace("{action}", request.action.past_tense())
+        .replace(
+            "{duration}",
+            &request
+                .duration
+                .map(format_duration)
+                .unwrap_or_else(|| "indefinitely".into()),
+        )
+        .replace(
+            "{reason}",
+            if request.reason.is_empty() {
+                "No reason given"
+            } else {
+                &request.reason
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use twilight_model::{
+        guild::Permissions,
+        id::{GuildId, RoleId, UserId},
+    };
+
+    use super::{ModAction, ModCase, ModerationConfig};
+
+    #[test]
+    fn test_is_moderator() {
+        let mut config = ModerationConfig::new(GuildId(1));
+        let fallback = Permissions::BAN_MEMBERS;
+
+        assert!(config.is_moderator(Permissions::BAN_MEMBERS, &[], fallback));
+        assert!(config.is_moderator(Permissions::MANAGE_GUILD, &[], fallback));
+        assert!(!config.is_moderator(Permissions::empty(), &[RoleId(2)], fallback));
+
+        config.moderator_roles = vec![RoleId(2)];
+        config.admin_roles = vec![RoleId(3)];
+        assert!(!config.is_moderator(Permissions::BAN_MEMBERS, &[], fallback));
+        assert!(config.is_moderator(Permissions::empty(), &[RoleId(2)], fallback));
+        assert!(config.is_moderator(Permissions::empty(), &[RoleId(3)], fallback));
+        assert!(!config.is_admin(Permissions::empty(), &[RoleId(2)]));
+        assert!(config.is_admin(Permissions::empty(), &[RoleId(3)]));
+    }
+
+    #[test]
+    fn test_is_replaced() {
+        let created_at = NaiveDate::from_ymd(2021, 9, 1).and_hms(12, 0, 0);
+        let case = |id, action, expired| ModCase {
+            id,
+            guild_id: GuildId(1),
+            case_number: id,
+            action,
+            target_id: UserId(2),
+            moderator_id: UserId(3),
+            reason: String::new(),
+            duration: None,
+            created_at,
+            expired_at: if expired { Some(created_at) } else { None },
+            related_cases: vec![],
+        };
+
+        let tempban = case(2, ModAction::Tempban, false);
+        assert!(!tempban.is_replaced(&[tempban.clone()]));
+        assert!(!tempban.is_replaced(&[case(1, ModAction::Ban, false)]));
+        assert!(!tempban.is_replaced(&[case(3, ModAction::Mute, false)]));
+        assert!(!tempban.is_replaced(&[case(3, ModAction::Tempban, true)]));
+        assert!(tempban.is_replaced(&[case(3, ModAction::Ban, false)]));
+        assert!(tempban.is_replaced(&[case(3, ModAction::Tempban, false)]));
+
+        let mute = case(2, ModAction::Mute, false);
+        assert!(!mute.is_replaced(&[case(3, ModAction::Ban, false)]));
+        assert!(mute.is_replaced(&[case(3, ModAction::Mute, false)]));
+    }
+}
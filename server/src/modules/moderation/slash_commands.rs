@@ -0,0 +1,416 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use twilight_model::{
+    application::{
+        command::{
+            BaseCommandOptionData, ChoiceCommandOptionData, Command, CommandOption,
+            OptionsCommandOptionData,
+        },
+        interaction::ApplicationCommand,
+    },
+    guild::Permissions,
+    id::UserId,
+};
+
+use crate::{
+    bot::{errors::UserError, event_handler::EventHandler},
+    modules::{
+        moderation::{
+            cases::history_page, check_hierarchy, execute, is_admin, is_moderator, validate_reason,
+            ModAction, ModCase, ModRequest,
+        },
+        slash_commands::{
+            chat_input, CommandArgs, CommandOptions, SlashCommand, SlashCommandError,
+        },
+    },
+    util::time::parse_duration,
+};
+
+pub struct ModArgs {
+    pub user: UserId,
+    pub reason: String,
+    pub duration: Option<String>,
+}
+
+impl CommandArgs for ModArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(Self {
+            user: UserId(options.required("user")?),
+            reason: options.string("reason").unwrap_or_default().trim().into(),
+            duration: options.string("duration").map(String::from),
+        })
+    }
+}
+
+/// One of `/warn`, `/kick`, `/ban`, `/tempban`, `/mute` and `/note`, which all take a member
+/// and a reason, and for temporary actions a duration.
+pub struct ModCommand(pub ModAction);
+
+impl ModCommand {
+    /// What a member needs to use the command while the guild has no moderator roles.
+    fn fallback_permissions(&self) -> Permissions {
+        match self.0 {
+            ModAction::Warn | ModAction::Note => Permissions::MANAGE_MESSAGES,
+            ModAction::Kick => Permissions::KICK_MEMBERS,
+            ModAction::Ban | ModAction::Tempban => Permissions::BAN_MEMBERS,
+            ModAction::Mute => Permissions::MANAGE_ROLES,
+        }
+    }
+}
+
+#[async_trait]
+impl SlashCommand for ModCommand {
+    type Args = ModArgs;
+
+    fn definition(&self) -> Command {
+        let user = CommandOption::User(BaseCommandOptionData {
+            description: "The member to take action against".into(),
+            name: "user".into(),
+            required: true,
+        });
+        let reason = CommandOption::String(ChoiceCommandOptionData {
+            choices: vec![],
+            description: "Why, recorded in the case and the audit log".into(),
+            name: "reason".into(),
+            required: matches!(self.0, ModAction::Warn | ModAction::Note),
+        });
+        let duration = |required| {
+            CommandOption::String(ChoiceCommandOptionData {
+                choices: vec![],
+                description: "How long for, like 30m, 1d or 2w".into(),
+                name: "duration".into(),
+                required,
+            })
+        };
+
+        let (description, options) = match self.0 {
+            ModAction::Warn => ("Warn a member", vec![user, reason]),
+            ModAction::Kick => ("Kick a member from the server", vec![user, reason]),
+            ModAction::Ban => ("Ban a member from the server", vec![user, reason]),
+            ModAction::Tempban => (
+                "Ban a member from the server for a while",
+                vec![user, duration(true), reason],
+            ),
+            ModAction::Mute => (
+                "Give a member the mute role",
+                vec![user, duration(false), reason],
+            ),
+            ModAction::Note => (
+                "Add a note to a member's case history without taking action",
+                vec![user, reason],
+            ),
+        };
+        chat_input(self.0.as_str(), description, options)
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: ModArgs,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Moderation commands can only be used in a guild")?;
+        let member = command
+            .member
+            .as_ref()
+            .ok_or("Moderation command has no member")?;
+        let moderator = member
+            .user
+            .clone()
+            .ok_or("Moderation command has no user")?;
+
+        if !is_moderator(
+            &event_handler.bot.db,
+            guild_id,
+            member,
+            self.fallback_permissions(),
+        )
+        .await?
+        {
+            return Err(UserError::MissingPermissions(format!(
+                "Only moderators can {} members",
+                self.0.as_str()
+            ))
+            .into());
+        }
+        if args.user == moderator.id {
+            return Err(
+                UserError::BadInput(format!("You can't {} yourself", self.0.as_str())).into(),
+            );
+        }
+
+        let duration = args
+            .duration
+            .as_deref()
+            .map(|input| {
+                parse_duration(input).ok_or_else(|| {
+                    UserError::BadInput(format!(
+                        "Couldn't understand \"{}\" as a duration. Try something like `30m`, `1d` or `2w`.",
+                        input
+                    ))
+                })
+            })
+            .transpose()?;
+        if self.0 == ModAction::Tempban && duration.is_none() {
+            return Err(SlashCommandError::MissingOption("duration".into()).into());
+        }
+        validate_reason(self.0, &args.reason).map_err(UserError::BadInput)?;
+
+        // Notes are only for moderators, so they aren't posted in the channel.
+        event_handler
+            .defer_interaction_reply(command, self.0 == ModAction::Note)
+            .await?;
+
+        let http = &event_handler.bot.http;
+        // Notes don't do anything to the member, so they can be left on anyone.
+        if self.0 != ModAction::Note {
+            check_hierarchy(
+                self.0,
+                guild_id,
+                moderator.id,
+                &member.roles,
+                args.user,
+                http,
+            )
+            .await?;
+        }
+        let target = http.user(args.user).exec().await?.model().await?;
+        let cases = execute(
+            ModRequest {
+                action: self.0,
+                guild_id,
+                target,
+                moderator,
+                reason: args.reason,
+                duration,
+            },
+            &event_handler.bot.db,
+            http,
+        )
+        .await?;
+
+        event_handler
+            .update_interaction_reply(
+                command,
+                None,
+                &cases
+                    .iter()
+                    .map(ModCase::embed)
+                    .collect::<Result<Vec<_>, _>>()?,
+                &[],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub struct CasesArgs {
+    pub user: UserId,
+    pub page: i64,
+}
+
+impl CommandArgs for CasesArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(Self {
+            user: UserId(options.required("user")?),
+            page: options.integer("page").unwrap_or(1),
+        })
+    }
+}
+
+/// `/cases`, the case history of a member.
+pub struct CasesCommand;
+
+#[async_trait]
+impl SlashCommand for CasesCommand {
+    type Args = CasesArgs;
+
+    fn definition(&self) -> Command {
+        chat_input(
+            "cases",
+            "Show the case history of a member",
+            vec![
+                CommandOption::User(BaseCommandOptionData {
+                    description: "The member whose cases to show".into(),
+                    name: "user".into(),
+                    required: true,
+                }),
+                CommandOption::Integer(ChoiceCommandOptionData {
+                    choices: vec![],
+                    description: "The page to start on".into(),
+                    name: "page".into(),
+                    required: false,
+                }),
+            ],
+        )
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: CasesArgs,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Moderation commands can only be used in a guild")?;
+        let member = command
+            .member
+            .as_ref()
+            .ok_or("Moderation command has no member")?;
+        let db = &event_handler.bot.db;
+        if !is_moderator(db, guild_id, member, Permissions::MANAGE_MESSAGES).await? {
+            return Err(UserError::MissingPermissions(
+                "Only moderators can see case history".into(),
+            )
+            .into());
+        }
+
+        event_handler.defer_interaction_reply(command, true).await?;
+
+        let (embed, components) = history_page(db, guild_id, args.user, args.page - 1).await?;
+        event_handler
+            .update_interaction_reply(command, None, &[embed], &components)
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub enum CaseArgs {
+    View(i64),
+    Edit(i64, String),
+    Delete(i64),
+}
+
+impl CommandArgs for CaseArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        let (name, options) = options.subcommand()?;
+        let number = options.required_integer("number")?;
+        match name {
+            "view" => Ok(CaseArgs::View(number)),
+            "edit" => Ok(CaseArgs::Edit(
+                number,
+                options.required_string("reason")?.trim().into(),
+            )),
+            "delete" => Ok(CaseArgs::Delete(number)),
+            _ => Err(SlashCommandError::CannotProcessUnknownCommand),
+        }
+    }
+}
+
+/// `/case`, to look at, correct or remove a single case.
+pub struct CaseCommand;
+
+#[async_trait]
+impl SlashCommand for CaseCommand {
+    type Args = CaseArgs;
+
+    fn definition(&self) -> Command {
+        let number = || {
+            CommandOption::Integer(ChoiceCommandOptionData {
+                choices: vec![],
+                description: "The case number".into(),
+                name: "number".into(),
+                required: true,
+            })
+        };
+
+        chat_input(
+            "case",
+            "View and manage moderation cases",
+            vec![
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "Show a case".into(),
+                    name: "view".into(),
+                    options: vec![number()],
+                }),
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "Change the reason of a case".into(),
+                    name: "edit".into(),
+                    options: vec![
+                        number(),
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![],
+                            description: "The new reason".into(),
+                            name: "reason".into(),
+                            required: true,
+                        }),
+                    ],
+                }),
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "Remove a case from the history".into(),
+                    name: "delete".into(),
+                    options: vec![number()],
+                }),
+            ],
+        )
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: CaseArgs,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Moderation commands can only be used in a guild")?;
+        let member = command
+            .member
+            .as_ref()
+            .ok_or("Moderation command has no member")?;
+        let db = &event_handler.bot.db;
+
+        let allowed = match args {
+            CaseArgs::Delete(_) => is_admin(db, guild_id, member).await?,
+            _ => is_moderator(db, guild_id, member, Permissions::MANAGE_MESSAGES).await?,
+        };
+        if !allowed {
+            return Err(UserError::MissingPermissions(match args {
+                CaseArgs::Delete(_) => "Only admins can delete cases".into(),
+                _ => "Only moderators can manage cases".into(),
+            })
+            .into());
+        }
+
+        let number = match &args {
+            CaseArgs::View(number) | CaseArgs::Edit(number, _) | CaseArgs::Delete(number) => {
+                *number
+            }
+        };
+        let mut case = db
+            .moderation()
+            .fetch_case(guild_id, number)
+            .await?
+            .ok_or_else(|| UserError::BadInput(format!("There's no case #{}", number)))?;
+
+        match args {
+            CaseArgs::View(_) => {}
+            CaseArgs::Edit(_, reason) => {
+                validate_reason(case.action, &reason).map_err(UserError::BadInput)?;
+                db.moderation()
+                    .update_case_reason(guild_id, number, &reason)
+                    .await?;
+                case.reason = reason;
+            }
+            CaseArgs::Delete(_) => {
+                db.moderation().delete_case(guild_id, number).await?;
+                return event_handler
+                    .ephemeral_interaction_reply(command, &format!("Deleted case #{}", number))
+                    .await;
+            }
+        }
+
+        event_handler.defer_interaction_reply(command, true).await?;
+        event_handler
+            .update_interaction_reply(command, None, &[case.embed()?], &[])
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,66 +1,328 @@
-use chrono::{NaiveDateTime, Utc};
+use std::{error::Error, str::FromStr};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
 use twilight_model::{
-    application::interaction::{application_command::CommandDataOption, ApplicationCommand},
-    id::EmojiId,
+    application::interaction::ApplicationCommand,
+    id::{ChannelId, GuildId, MessageId},
     user::User,
 };
 
+use crate::{bot::errors::UserError, models::emoji::EmojiKey, util::time::parse_time};
+
+pub mod results;
+pub mod scheduler;
 pub mod slash_commands;
+pub mod votes;
+
+use self::slash_commands::PollCreateArgs;
 
 pub struct Poll {
     pub id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    /// Set once the poll has been posted.
+    pub message_id: Option<MessageId>,
     pub author: User,
     pub question: String,
     pub start: Option<NaiveDateTime>,
     pub ends: Option<NaiveDateTime>,
     pub options: Option<Vec<PollOption>>,
+    pub voting: PollVoting,
+    /// Only show vote counts once the poll closes. Reaction counts are always visible,
+    /// so this only applies to button voting.
+    pub hide_results: bool,
 }
 
-impl From<&ApplicationCommand> for Poll {
-    fn from(command: &ApplicationCommand) -> Self {
-        let author = command.member.clone().unwrap().user.unwrap();
-        let (question, start, ends, options) = command.data.options.iter().fold(
-            ("<Unknown Question>".to_string(), None, None, None),
-            |mut res, option| {
-                match option.name() {
-                    "question" => match option {
-                        CommandDataOption::String { value, .. } => res.0 = value.clone(),
-                        _ => {}
-                    },
-                    "ends" => match option {
-                        CommandDataOption::String { value, .. } => {
-                            res.1 = Some(Utc::now().naive_utc());
-                            let date = date_time_parser::DateParser::parse(value);
-                            let time = date_time_parser::TimeParser::parse(value);
-                            match (date, time) {
-                                (None, None) => res.1 = None,
-                                (None, Some(time)) => {
-                                    let date = Utc::now().naive_utc().date();
-                                    res.2 = Some(date.and_time(time));
-                                }
-                                (Some(date), None) => res.2 = Some(date.and_hms(0, 0, 0)),
-                                (Some(date), Some(time)) => res.2 = Some(date.and_time(time)),
-                            }
-                        }
-                        _ => {}
-                    },
-                    _ => {}
-                }
-                res
-            },
-        );
+/// How members cast their votes on a poll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollVoting {
+    /// Vote by reacting with a choice's emoji, visible to everyone.
+    Reactions,
+    /// Vote with buttons, one vote per member, stored in the `poll_vote` table.
+    Buttons,
+}
+
+impl PollVoting {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollVoting::Reactions => "reactions",
+            PollVoting::Buttons => "buttons",
+        }
+    }
+}
+
+impl FromStr for PollVoting {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reactions" => Ok(PollVoting::Reactions),
+            "buttons" => Ok(PollVoting::Buttons),
+            _ => Err(format!("Invalid poll voting: {}", s).into()),
+        }
+    }
+}
+
+/// The most choices a single poll can offer.
+pub const MAX_POLL_CHOICES: usize = 10;
+
+impl Poll {
+    /// Build a poll from the `/poll create` command. The end time is parsed relative
+    /// to `now`, with absolute times read in `timezone`.
+    pub fn new(
+        command: &ApplicationCommand,
+        args: PollCreateArgs,
+        now: DateTime<Utc>,
+        timezone: Tz,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or_else(|| UserError::BadInput("Polls can only be used in a server".into()))?;
+        let author = command
+            .member
+            .as_ref()
+            .and_then(|member| member.user.clone())
+            .ok_or("Poll command has no author")?;
 
-        Self {
+        let (start, ends) = match &args.ends {
+            Some(ends) => (
+                Some(now.naive_utc()),
+                Some(parse_time(ends, now, timezone)?.naive_utc()),
+            ),
+            None => (None, None),
+        };
+
+        let options = if args.choices.is_empty() {
+            None
+        } else {
+            Some(
+                args.choices
+                    .iter()
+                    .take(MAX_POLL_CHOICES)
+                    .enumerate()
+                    .map(|(i, choice)| PollOption::parse(choice, args.labels.emoji(i)))
+                    .collect(),
+            )
+        };
+
+        Ok(Self {
             id: 0,
+            guild_id,
+            channel_id: command.channel_id,
+            message_id: None,
             author,
-            question,
+            question: args.question,
             start,
             ends,
             options,
-        }
+            voting: args.voting,
+            hide_results: args.hide_results,
+        })
     }
 }
 
+/// A poll as stored in the `poll` table.
+#[derive(sqlx::FromRow, serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PollRow {
+    pub id: i64,
+    pub guild_id: String,
+    pub author_id: String,
+    pub question: String,
+    pub start: Option<NaiveDateTime>,
+    pub ends: Option<NaiveDateTime>,
+    pub channel_id: Option<String>,
+    pub message_id: Option<String>,
+    pub closed: bool,
+    pub voting: String,
+    pub hide_results: bool,
+}
+
+/// One of the choices a poll offers, voted for by reacting with its emoji.
+#[derive(Clone, Debug, PartialEq)]
 pub struct PollOption {
-    emoji: EmojiId,
+    pub emoji: EmojiKey,
+    pub name: String,
+}
+
+impl PollOption {
+    /// Parse a choice as typed by the poll author. A leading emoji, either unicode or a
+    /// custom emoji like `<:name:id>`, is used for voting; otherwise `fallback` is.
+    pub fn parse(choice: &str, fallback: EmojiKey) -> Self {
+        let choice = choice.trim();
+        let (first, rest) = match choice.split_once(char::is_whitespace) {
+            Some((first, rest)) => (first, rest.trim()),
+            None => (choice, ""),
+        };
+        let emoji = if first.starts_with('<') && first.ends_with('>') {
+            match first.parse() {
+                Ok(emoji @ EmojiKey::Custom { .. }) => Some(emoji),
+                _ => None,
+            }
+        } else if is_unicode_emoji(first) {
+            Some(EmojiKey::Unicode(first.to_string()))
+        } else {
+            None
+        };
+        match emoji {
+            Some(emoji) if !rest.is_empty() => PollOption {
+                emoji,
+                name: rest.to_string(),
+            },
+            _ => PollOption {
+                emoji: fallback,
+                name: choice.to_string(),
+            },
+        }
+    }
+
+    /// How the choice is listed in the poll embed.
+    pub fn label(&self) -> String {
+        format!("{} {}", self.emoji.as_message_text(), self.name)
+    }
+}
+
+/// Whether `s` is made up only of emoji, along with the joiners, variation selectors and
+/// keycap marks they're built from, so punctuation like `-` or `??` isn't taken for one.
+fn is_unicode_emoji(s: &str) -> bool {
+    let is_emoji = |c: char| {
+        matches!(c as u32,
+            0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x2300..=0x23FF
+            | 0x2190..=0x21FF | 0x3030 | 0x303D | 0x3297 | 0x3299 | 0xA9 | 0xAE | 0x2122)
+    };
+    let is_modifier = |c: char| {
+        matches!(
+            c as u32,
+            0x200D | 0xFE0E | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F
+        )
+    };
+    let is_keycap = matches!(
+        s.chars().collect::<Vec<_>>().as_slice(),
+        ['0'..='9' | '#' | '*', '\u{FE0F}', '\u{20E3}'] | ['0'..='9' | '#' | '*', '\u{20E3}']
+    );
+    is_keycap || (s.chars().any(is_emoji) && s.chars().all(|c| is_emoji(c) || is_modifier(c)))
+}
+
+/// The first emoji used by more than one of `options`, which would make their votes
+/// impossible to tell apart.
+pub fn duplicate_emoji(options: &[PollOption]) -> Option<&EmojiKey> {
+    options.iter().enumerate().find_map(|(i, option)| {
+        options[..i]
+            .iter()
+            .any(|other| other.emoji == option.emoji)
+            .then(|| &option.emoji)
+    })
+}
+
+/// Which emoji are handed out to choices that don't bring their own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PollLabels {
+    Numbers,
+    Letters,
+}
+
+impl PollLabels {
+    pub fn emoji(&self, index: usize) -> EmojiKey {
+        let emoji = match self {
+            PollLabels::Numbers if index == 9 => "\u{1F51F}".to_string(),
+            PollLabels::Numbers => format!("{}\u{FE0F}\u{20E3}", index + 1),
+            PollLabels::Letters => std::char::from_u32(0x1F1E6 + index as u32)
+                .unwrap()
+                .to_string(),
+        };
+        EmojiKey::Unicode(emoji)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::EmojiId;
+
+    use crate::models::emoji::EmojiKey;
+
+    use super::{duplicate_emoji, PollLabels, PollOption};
+
+    #[test]
+    fn test_label_emoji() {
+        assert_eq!(
+            PollLabels::Numbers.emoji(0),
+            EmojiKey::Unicode("1️⃣".to_string())
+        );
+        assert_eq!(
+            PollLabels::Numbers.emoji(9),
+            EmojiKey::Unicode("🔟".to_string())
+        );
+        assert_eq!(
+            PollLabels::Letters.emoji(2),
+            EmojiKey::Unicode("🇨".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_poll_option() {
+        let fallback = PollLabels::Numbers.emoji(0);
+        assert_eq!(
+            PollOption::parse("🍕 Pizza", fallback.clone()),
+            PollOption {
+                emoji: EmojiKey::Unicode("🍕".to_string()),
+                name: "Pizza".to_string(),
+            }
+        );
+        assert_eq!(
+            PollOption::parse("<:pepe:1234> Pepe", fallback.clone()),
+            PollOption {
+                emoji: EmojiKey::Custom {
+                    id: EmojiId(1234),
+                    animated: false,
+                },
+                name: "Pepe".to_string(),
+            }
+        );
+        assert_eq!(
+            PollOption::parse("Hot dogs", fallback.clone()),
+            PollOption {
+                emoji: fallback.clone(),
+                name: "Hot dogs".to_string(),
+            }
+        );
+        assert_eq!(
+            PollOption::parse("- foo", fallback.clone()),
+            PollOption {
+                emoji: fallback.clone(),
+                name: "- foo".to_string(),
+            }
+        );
+        assert_eq!(
+            PollOption::parse("?? bar", fallback.clone()).emoji,
+            fallback
+        );
+        assert_eq!(
+            PollOption::parse("<b> bold", fallback.clone()).emoji,
+            fallback
+        );
+        assert_eq!(
+            PollOption::parse("👍🏽 Yes", fallback).emoji,
+            EmojiKey::Unicode("👍🏽".to_string())
+        );
+    }
+
+    #[test]
+    fn test_duplicate_emoji() {
+        let options = vec![
+            PollOption::parse("Pizza", PollLabels::Numbers.emoji(0)),
+            PollOption::parse("2️⃣ Pasta", PollLabels::Numbers.emoji(1)),
+            PollOption::parse("Salad", PollLabels::Numbers.emoji(2)),
+        ];
+        assert_eq!(duplicate_emoji(&options), None);
+
+        let options = vec![
+            PollOption::parse("2️⃣ Pizza", PollLabels::Numbers.emoji(0)),
+            PollOption::parse("Pasta", PollLabels::Numbers.emoji(1)),
+        ];
+        assert_eq!(
+            duplicate_emoji(&options),
+            Some(&EmojiKey::Unicode("2️⃣".to_string()))
+        );
+    }
 }
@@ -0,0 +1,209 @@
+use twilight_embed_builder::EmbedFooterBuilder;
+use twilight_model::channel::{
+    embed::{Embed, EmbedField},
+    Message, ReactionType,
+};
+
+use crate::models::emoji::EmojiKey;
+
+use super::PollOption;
+
+/// Width of the bar drawn for each option in the results.
+const BAR_LENGTH: usize = 10;
+
+/// The votes counted for a single poll option.
+#[derive(Debug, PartialEq)]
+pub struct PollTally {
+    pub label: String,
+    pub votes: u64,
+}
+
+/// Count the votes on a poll message.
+///
+/// The bot reacts with every option when posting the poll, so only reactions
+/// it took part in are options, and its own reaction isn't a vote.
+pub fn tally_reactions(message: &Message) -> Vec<PollTally> {
+    message
+        .reactions
+        .iter()
+        .filter(|reaction| reaction.me)
+        .map(|reaction| PollTally {
+            label: reaction_label(&reaction.emoji),
+            votes: reaction.count.saturating_sub(1),
+        })
+        .collect()
+}
+
+/// Count the votes for each of a poll's choices, in the order they were offered.
+pub fn tally_choices(message: &Message, choices: &[PollOption]) -> Vec<PollTally> {
+    choices
+        .iter()
+        .map(|choice| {
+            let votes = message
+                .reactions
+                .iter()
+                .find(|reaction| EmojiKey::from(&reaction.emoji) == choice.emoji)
+                .map(|reaction| {
+                    if reaction.me {
+                        reaction.count.saturating_sub(1)
+                    } else {
+                        reaction.count
+                    }
+                })
+                .unwrap_or(0);
+            PollTally {
+                label: choice.label(),
+                votes,
+            }
+        })
+        .collect()
+}
+
+/// Count the button votes for each of a poll's choices from `(position, votes)` pairs.
+pub fn tally_votes(choices: &[PollOption], counts: &[(i64, i64)]) -> Vec<PollTally> {
+    choices
+        .iter()
+        .enumerate()
+        .map(|(position, choice)| PollTally {
+            label: choice.label(),
+            votes: counts
+                .iter()
+                .find(|(p, _)| *p == position as i64)
+                .map(|(_, votes)| *votes as u64)
+                .unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Replace the poll embed's fields with a bar for each option.
+pub fn tallies_embed(mut embed: Embed, tallies: &[PollTally]) -> Embed {
+    let total: u64 = tallies.iter().map(|tally| tally.votes).sum();
+
+    embed.fields = tallies
+        .iter()
+        .map(|tally| EmbedField {
+            inline: false,
+            name: tally.label.clone(),
+            value: results_bar(tally.votes, total),
+        })
+        .collect();
+
+    embed
+}
+
+/// Replace the poll embed's footer and fields with the final results.
+pub fn results_embed(embed: Embed, tallies: &[PollTally]) -> Embed {
+    totals_embed(embed, tallies, "Poll closed")
+}
+
+/// Replace the poll embed's footer and fields with the results so far.
+pub fn standings_embed(embed: Embed, tallies: &[PollTally]) -> Embed {
+    totals_embed(embed, tallies, "Current results")
+}
+
+fn totals_embed(embed: Embed, tallies: &[PollTally], status: &str) -> Embed {
+    let total: u64 = tallies.iter().map(|tally| tally.votes).sum();
+    let mut embed = tallies_embed(embed, tallies);
+
+    embed.footer.replace(
+        EmbedFooterBuilder::new(format!(
+            "{} | {} vote{}",
+            status,
+            total,
+            if total == 1 { "" } else { "s" }
+        ))
+        .build(),
+    );
+
+    embed
+}
+
+fn results_bar(votes: u64, total: u64) -> String {
+    let percent = if total == 0 {
+        0
+    } else {
+        (votes * 100 + total / 2) / total
+    };
+    let filled = (percent as usize * BAR_LENGTH + 50) / 100;
+    format!(
+        "{}{} {}% ({})",
+        "█".repeat(filled),
+        "░".repeat(BAR_LENGTH - filled),
+        percent,
+        votes
+    )
+}
+
+fn reaction_label(emoji: &ReactionType) -> String {
+    match emoji {
+        ReactionType::Custom {
+            animated,
+            id,
+            name: Some(name),
+        } => format!("<{}:{}:{}>", if *animated { "a" } else { "" }, name, id),
+        ReactionType::Custom { id, .. } => id.to_string(),
+        ReactionType::Unicode { name } => name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_embed_builder::EmbedBuilder;
+
+    use crate::modules::poll::PollOption;
+
+    use super::{results_bar, results_embed, tally_votes, PollTally};
+
+    #[test]
+    fn test_results_bar() {
+        assert_eq!(results_bar(0, 0), "░░░░░░░░░░ 0% (0)");
+        assert_eq!(results_bar(3, 4), "████████░░ 75% (3)");
+        assert_eq!(results_bar(1, 3), "███░░░░░░░ 33% (1)");
+        assert_eq!(results_bar(5, 5), "██████████ 100% (5)");
+    }
+
+    #[test]
+    fn test_results_embed() {
+        let embed = EmbedBuilder::new()
+            .description("Pineapple on pizza?")
+            .build()
+            .expect("Failed to build test embed");
+        let tallies = vec![
+            PollTally {
+                label: "👍".into(),
+                votes: 1,
+            },
+            PollTally {
+                label: "👎".into(),
+                votes: 3,
+            },
+        ];
+
+        let embed = results_embed(embed, &tallies);
+
+        assert_eq!(embed.fields.len(), 2);
+        assert_eq!(embed.fields[0].name, "👍");
+        assert_eq!(embed.fields[1].value, "████████░░ 75% (3)");
+        assert_eq!(embed.footer.unwrap().text, "Poll closed | 4 votes");
+    }
+
+    #[test]
+    fn test_tally_votes() {
+        let choices = vec![
+            PollOption {
+                emoji: "🍕".parse().unwrap(),
+                name: "Pizza".into(),
+            },
+            PollOption {
+                emoji: "🌮".parse().unwrap(),
+                name: "Tacos".into(),
+            },
+        ];
+
+        let tallies = tally_votes(&choices, &[(1, 2)]);
+
+        assert_eq!(tallies[0].label, "🍕 Pizza");
+        assert_eq!(tallies[0].votes, 0);
+        assert_eq!(tallies[1].votes, 2);
+    }
+}
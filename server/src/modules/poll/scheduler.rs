@@ -0,0 +1,86 @@
+use std::error::Error;
+
+use twilight_http::Client;
+use twilight_model::{
+    channel::Message,
+    id::{ChannelId, MessageId},
+};
+
+use crate::db::Database;
+
+use super::{
+    results::{results_embed, tally_choices, tally_reactions, tally_votes, PollTally},
+    PollRow, PollVoting,
+};
+
+/// Close the poll with `poll_id` once its end time has passed, run by the job scheduler.
+///
+/// Polls that were deleted or already closed by hand are skipped.
+pub async fn expire_poll(
+    poll_id: i64,
+    db: &Database,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match db.poll().fetch_poll(poll_id).await? {
+        Some(poll) if !poll.closed => close_poll(&poll, db, http).await,
+        _ => Ok(()),
+    }
+}
+
+/// Fetch a poll's message and count the votes on it so far.
+pub async fn tally_poll(
+    poll: &PollRow,
+    db: &Database,
+    http: &Client,
+) -> Result<(Message, Vec<PollTally>), Box<dyn Error + Send + Sync>> {
+    let (channel_id, message_id) = match (&poll.channel_id, &poll.message_id) {
+        (Some(channel_id), Some(message_id)) => (
+            ChannelId(channel_id.parse()?),
+            MessageId(message_id.parse()?),
+        ),
+        _ => return Err("Poll was never posted".into()),
+    };
+
+    let message = http
+        .message(channel_id, message_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let choices = db.poll().fetch_poll_choices(poll.id).await?;
+    let tallies = match poll.voting.parse::<PollVoting>()? {
+        PollVoting::Buttons => {
+            let counts = db.poll().fetch_vote_counts(poll.id).await?;
+            tally_votes(&choices, &counts)
+        }
+        PollVoting::Reactions if choices.is_empty() => tally_reactions(&message),
+        PollVoting::Reactions => tally_choices(&message, &choices),
+    };
+
+    Ok((message, tallies))
+}
+
+/// Count the votes on a poll, edit its message to show the results and mark it closed.
+pub async fn close_poll(
+    poll: &PollRow,
+    db: &Database,
+    http: &Client,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (message, tallies) = tally_poll(poll, db, http).await?;
+
+    if let Some(embed) = message.embeds.into_iter().next() {
+        let embed = results_embed(embed, &tallies);
+        let mut update = http
+            .update_message(message.channel_id, message.id)
+            .embeds(&[embed])?;
+        if poll.voting.parse::<PollVoting>()? == PollVoting::Buttons {
+            update = update.components(Some(&[]))?;
+        }
+        update.exec().await?;
+    }
+
+    db.poll().close_poll(poll.id).await?;
+
+    Ok(())
+}
@@ -1,25 +1,264 @@
 use std::error::Error;
 
-use chrono::{Local, TimeZone};
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
 use twilight_embed_builder::{EmbedAuthorBuilder, EmbedBuilder, EmbedFooterBuilder};
-use twilight_http::request::prelude::RequestReactionType;
-use twilight_model::application::interaction::ApplicationCommand;
+use twilight_model::{
+    application::{
+        command::{
+            BaseCommandOptionData, ChoiceCommandOptionData, Command, CommandOption,
+            CommandOptionChoice, OptionsCommandOptionData,
+        },
+        interaction::ApplicationCommand,
+    },
+    guild::{Emoji, Permissions},
+    id::{EmojiId, GuildId},
+};
 
-use crate::{bot::event_handler::EventHandler, modules::poll::Poll};
+use crate::{
+    bot::{errors::UserError, event_handler::EventHandler},
+    models::emoji::EmojiKey,
+    modules::{
+        moderation,
+        poll::{
+            duplicate_emoji,
+            results::{results_embed, standings_embed},
+            scheduler, votes, Poll, PollLabels, PollOption, PollRow, PollVoting, MAX_POLL_CHOICES,
+        },
+        slash_commands::{
+            chat_input, CommandArgs, CommandOptions, SlashCommand, SlashCommandError,
+        },
+        timezone::resolve_timezone,
+    },
+    scheduler::Job,
+};
 
-pub struct PollCommand<'a>(pub &'a Box<ApplicationCommand>);
+/// The options of `/poll create`.
+pub struct PollCreateArgs {
+    pub question: String,
+    pub ends: Option<String>,
+    pub labels: PollLabels,
+    pub voting: PollVoting,
+    pub hide_results: bool,
+    pub choices: Vec<String>,
+}
+
+impl CommandArgs for PollCreateArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(Self {
+            question: options.required_string("question")?.into(),
+            ends: options.string("ends").map(String::from),
+            labels: match options.string("labels") {
+                Some("letters") => PollLabels::Letters,
+                _ => PollLabels::Numbers,
+            },
+            voting: options.parse("voting")?.unwrap_or(PollVoting::Reactions),
+            hide_results: options.boolean("hide_results").unwrap_or(false),
+            choices: (1..=MAX_POLL_CHOICES)
+                .filter_map(|i| options.string(&format!("choice_{}", i)))
+                .map(String::from)
+                .collect(),
+        })
+    }
+}
+
+pub enum PollArgs {
+    Create(PollCreateArgs),
+    List,
+    End(i64),
+    Results(i64),
+}
+
+impl CommandArgs for PollArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        let (name, options) = options.subcommand()?;
+        match name {
+            "create" => Ok(PollArgs::Create(PollCreateArgs::from_options(&options)?)),
+            "list" => Ok(PollArgs::List),
+            "end" => Ok(PollArgs::End(options.required_integer("id")?)),
+            "results" => Ok(PollArgs::Results(options.required_integer("id")?)),
+            _ => Err(SlashCommandError::CannotProcessUnknownCommand),
+        }
+    }
+}
+
+pub struct PollCommand;
+
+#[async_trait]
+impl SlashCommand for PollCommand {
+    type Args = PollArgs;
+
+    fn definition(&self) -> Command {
+        let poll_id = || {
+            vec![CommandOption::Integer(ChoiceCommandOptionData {
+                choices: vec![],
+                description: "The poll's id, as shown by /poll list".into(),
+                name: "id".into(),
+                required: true,
+            })]
+        };
+
+        chat_input(
+            "poll",
+            "Create and manage polls",
+            vec![
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "Create a poll".into(),
+                    name: "create".into(),
+                    options: vec![
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![],
+                            description: "The question you are polling".into(),
+                            name: "question".into(),
+                            required: true,
+                        }),
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![],
+                            description: "When to stop polling, like 30m, 2h, 3d or tomorrow 5pm"
+                                .into(),
+                            name: "ends".into(),
+                            required: false,
+                        }),
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![
+                                CommandOptionChoice::String {
+                                    name: "Numbers".into(),
+                                    value: "numbers".into(),
+                                },
+                                CommandOptionChoice::String {
+                                    name: "Letters".into(),
+                                    value: "letters".into(),
+                                },
+                            ],
+                            description: "Emoji for choices that don't start with their own".into(),
+                            name: "labels".into(),
+                            required: false,
+                        }),
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![
+                                CommandOptionChoice::String {
+                                    name: "Reactions".into(),
+                                    value: "reactions".into(),
+                                },
+                                CommandOptionChoice::String {
+                                    name: "Buttons (one vote per member)".into(),
+                                    value: "buttons".into(),
+                                },
+                            ],
+                            description: "How members vote on the poll".into(),
+                            name: "voting".into(),
+                            required: false,
+                        }),
+                        CommandOption::Boolean(BaseCommandOptionData {
+                            description: "Hide button vote counts until the poll closes".into(),
+                            name: "hide_results".into(),
+                            required: false,
+                        }),
+                    ]
+                    .into_iter()
+                    .chain((1..=MAX_POLL_CHOICES).map(|i| {
+                        CommandOption::String(ChoiceCommandOptionData {
+                            choices: vec![],
+                            description: format!(
+                                "Choice #{}, optionally starting with an emoji",
+                                i
+                            ),
+                            name: format!("choice_{}", i),
+                            required: false,
+                        })
+                    }))
+                    .collect(),
+                }),
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "List the open polls in this server".into(),
+                    name: "list".into(),
+                    options: vec![],
+                }),
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "End a poll now and post its results".into(),
+                    name: "end".into(),
+                    options: poll_id(),
+                }),
+                CommandOption::SubCommand(OptionsCommandOptionData {
+                    description: "Show the results of a poll so far".into(),
+                    name: "results".into(),
+                    options: poll_id(),
+                }),
+            ],
+        )
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: PollArgs,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match args {
+            PollArgs::Create(args) => self.create(command, args, event_handler).await,
+            PollArgs::List => self.list(command, event_handler).await,
+            PollArgs::End(id) => self.end(command, id, event_handler).await,
+            PollArgs::Results(id) => self.results(command, id, event_handler).await,
+        }
+    }
+}
 
-impl<'a> PollCommand<'a> {
-    pub async fn process_command(
+impl PollCommand {
+    /// Post a new poll.
+    async fn create(
         &self,
-        event_handler: &EventHandler<'a>,
+        command: &Box<ApplicationCommand>,
+        args: PollCreateArgs,
+        event_handler: &EventHandler<'_>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let command = self.0;
+        let guild_id = command
+            .guild_id
+            .ok_or_else(|| UserError::BadInput("Polls can only be used in a server".into()))?;
+        let author_id = command
+            .member
+            .as_ref()
+            .and_then(|member| member.user.as_ref())
+            .ok_or("Poll command has no author")?
+            .id;
+        let timezone = resolve_timezone(&event_handler.bot.db, guild_id, author_id).await?;
+
+        let mut poll = Poll::new(command.as_ref(), args, Utc::now(), timezone)?;
+
+        if poll.options.as_ref().map(|o| o.len() == 1).unwrap_or(false) {
+            return Err(UserError::BadInput("A poll needs at least two choices".into()).into());
+        }
+        if let Some(emoji) = poll.options.as_deref().and_then(duplicate_emoji) {
+            return Err(UserError::BadInput(format!(
+                "More than one choice uses {}, give each choice its own emoji",
+                emoji.as_message_text()
+            ))
+            .into());
+        }
+        if let Some(options) = &mut poll.options {
+            self.check_custom_emoji(guild_id, options, event_handler)
+                .await?;
+        }
+
+        // Posting the poll takes a few requests, so reply once it's ready.
+        event_handler
+            .defer_interaction_reply(command, false)
+            .await?;
 
-        let poll = Poll::from(command.as_ref());
+        let description = match &poll.options {
+            Some(options) => format!(
+                "{}\n\n{}",
+                poll.question,
+                options
+                    .iter()
+                    .map(|option| option.label())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            None => poll.question.clone(),
+        };
 
         let mut embed = EmbedBuilder::new()
-            .description(poll.question.clone())
+            .description(description)
             .color(0xFFC0CB)
             .build()?;
 
@@ -39,9 +278,8 @@ impl<'a> PollCommand<'a> {
             EmbedFooterBuilder::new(match poll.ends {
                 Some(ends) => format!(
                     "Vote for Poll! | Ends {}",
-                    Local
-                        .from_local_datetime(&ends)
-                        .unwrap()
+                    timezone
+                        .from_utc_datetime(&ends)
                         .format("%b %-d, %Y at %-I:%M%P %Z")
                 ),
                 None => "Vote for Poll!".into(),
@@ -49,100 +287,421 @@ impl<'a> PollCommand<'a> {
             .build(),
         );
 
-        event_handler
-            .embed_interaction_reply(command, vec![embed])
+        let db = &event_handler.bot.db;
+
+        // Yes/no polls use the guild's poll emoji, falling back to 👍/👎
+        let (positive, negative) = match db.poll().fetch_poll_options(poll.guild_id).await {
+            Ok((positive, negative)) => {
+                // Only the stored ids are known, so look up whether they're animated.
+                let emojis = event_handler
+                    .bot
+                    .http
+                    .emojis(poll.guild_id)
+                    .exec()
+                    .await?
+                    .models()
+                    .await?;
+                let key = |id: EmojiId| EmojiKey::Custom {
+                    id,
+                    animated: emojis.iter().any(|emoji| emoji.id == id && emoji.animated),
+                };
+                (key(positive.0), key(negative.0))
+            }
+            Err(sqlx::Error::RowNotFound) => (
+                EmojiKey::Unicode("👍".into()),
+                EmojiKey::Unicode("👎".into()),
+            ),
+            Err(e) => return Err(e.into()),
+        };
+
+        let components = match poll.voting {
+            PollVoting::Buttons => {
+                let options = poll.options.get_or_insert_with(|| {
+                    vec![
+                        PollOption {
+                            emoji: positive.clone(),
+                            name: "Yes".into(),
+                        },
+                        PollOption {
+                            emoji: negative.clone(),
+                            name: "No".into(),
+                        },
+                    ]
+                });
+                votes::vote_components(options)
+            }
+            PollVoting::Reactions => vec![],
+        };
+
+        // The poll is saved before it's posted so votes on the message always find it.
+        db.poll().create_poll(&mut poll).await?;
+        let original_response = match event_handler
+            .update_interaction_reply(command, None, &[embed], &components)
+            .await
+        {
+            Ok(message) => message,
+            Err(e) => {
+                db.poll().delete_poll(poll.id).await?;
+                return Err(e);
+            }
+        };
+        db.poll()
+            .set_poll_message(poll.id, original_response.id)
             .await?;
+        if let Some(ends) = poll.ends {
+            db.scheduled_jobs()
+                .create_job(poll.guild_id, &Job::ClosePoll { poll_id: poll.id }, ends)
+                .await?;
+        }
+
+        let reactions = match (&poll.voting, &poll.options) {
+            (PollVoting::Buttons, _) => vec![],
+            (PollVoting::Reactions, Some(options)) => {
+                options.iter().map(|option| option.emoji.clone()).collect()
+            }
+            (PollVoting::Reactions, None) => vec![positive, negative],
+        };
+
+        for emoji in reactions.iter() {
+            event_handler
+                .bot
+                .http
+                .create_reaction(
+                    original_response.channel_id,
+                    original_response.id,
+                    &emoji.as_request_reaction(),
+                )
+                .exec()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// List the guild's open polls with links to their messages.
+    async fn list(
+        &self,
+        command: &Box<ApplicationCommand>,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Polls can only be listed in a guild")?;
 
-        let original_response = event_handler
+        let polls = event_handler
             .bot
-            .http
-            .get_interaction_original(&command.token)?
-            .exec()
-            .await?
-            .model()
+            .db
+            .poll()
+            .fetch_open_polls(guild_id)
             .await?;
+        if polls.is_empty() {
+            return event_handler
+                .ephemeral_interaction_reply(command, "There are no open polls")
+                .await;
+        }
 
-        // let (positive, negative) = (
-        //     RequestReactionType::Custom {
-        //         id: EmojiId(759194715294793778),
-        //         name: Some("PeepoYes".into()),
-        //     },
-        //     RequestReactionType::Custom {
-        //         id: EmojiId(759194735314731008),
-        //         name: Some("PeepoNo".into()),
-        //     },
-        // );
-
-        // let db = &event_handler.bot.db;
-        // db.poll()
-        //     .create_poll(&mut poll)
-        //     .await
-        //     .expect("could not create poll in db");
-
-        // let response;
-        // let (positive, negative) = match db.poll().fetch_poll_options(command.guild_id.unwrap()).await {
-        //     Ok(res) => {
-        //         response = res;
-        //         (
-        //             RequestReactionType::Custom {
-        //                 id: response.0 .0,
-        //                 name: Some(&response.0 .1),
-        //             },
-        //             RequestReactionType::Custom {
-        //                 id: response.1 .0,
-        //                 name: Some(&response.1 .1),
-        //             },
-        //         )
-        //     },
-        //     Err(_) => (
-        //         RequestReactionType::Unicode {
-        //             name: "👍".into()
-        //         },
-        //         RequestReactionType::Unicode {
-        //             name: "👎".into()
-        //         },
-        //     ),
-        // };
-
-        let positive = RequestReactionType::Unicode { name: "👍" };
-        let negative = RequestReactionType::Unicode { name: "👎" };
+        let lines = polls
+            .iter()
+            .map(|poll| {
+                let link = format!(
+                    "https://discord.com/channels/{}/{}/{}",
+                    poll.guild_id,
+                    poll.channel_id.as_deref().unwrap_or_default(),
+                    poll.message_id.as_deref().unwrap_or_default()
+                );
+                match poll.ends {
+                    Some(ends) => format!(
+                        "`#{}` [{}]({}) ends <t:{}:R>",
+                        poll.id,
+                        poll.question,
+                        link,
+                        ends.timestamp()
+                    ),
+                    None => format!("`#{}` [{}]({})", poll.id, poll.question, link),
+                }
+            })
+            .collect::<Vec<String>>();
 
         event_handler
-            .bot
-            .http
-            .create_reaction(
-                original_response.channel_id,
-                original_response.id,
-                &positive,
+            .ephemeral_interaction_reply(command, &lines.join("\n"))
+            .await
+    }
+
+    /// Close a poll early. Only its author or a moderator may end it.
+    async fn end(
+        &self,
+        command: &Box<ApplicationCommand>,
+        id: i64,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let poll = self.fetch_poll(command, id, event_handler).await?;
+
+        let member = command
+            .member
+            .as_ref()
+            .ok_or("Poll command has no member")?;
+        let is_author = member
+            .user
+            .as_ref()
+            .map(|user| user.id.to_string() == poll.author_id)
+            .unwrap_or(false);
+        let is_moderator = match command.guild_id {
+            Some(guild_id) => {
+                moderation::is_moderator(
+                    &event_handler.bot.db,
+                    guild_id,
+                    member,
+                    Permissions::MANAGE_MESSAGES,
+                )
+                .await?
+            }
+            None => false,
+        };
+        if !is_author && !is_moderator {
+            return Err(UserError::MissingPermissions(
+                "Only the poll's author or a moderator can end it".into(),
             )
-            .exec()
+            .into());
+        }
+        if poll.closed {
+            return Err(UserError::BadInput("That poll has already closed".into()).into());
+        }
+
+        event_handler.defer_interaction_reply(command, true).await?;
+        scheduler::close_poll(&poll, &event_handler.bot.db, &event_handler.bot.http).await?;
+
+        event_handler
+            .update_interaction_reply(
+                command,
+                Some(&format!("Poll `#{}` is closed", poll.id)),
+                &[],
+                &[],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Post the current results of a poll.
+    async fn results(
+        &self,
+        command: &Box<ApplicationCommand>,
+        id: i64,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let poll = self.fetch_poll(command, id, event_handler).await?;
+        if poll.hide_results && !poll.closed {
+            return Err(UserError::BadInput(
+                "The results of this poll are hidden until it closes".into(),
+            )
+            .into());
+        }
+
+        event_handler
+            .defer_interaction_reply(command, false)
             .await?;
+        let (message, tallies) =
+            scheduler::tally_poll(&poll, &event_handler.bot.db, &event_handler.bot.http).await?;
+        let embed = match message.embeds.into_iter().next() {
+            Some(embed) if poll.closed => results_embed(embed, &tallies),
+            Some(embed) => standings_embed(embed, &tallies),
+            None => return Err(format!("Poll {} message has no embed", poll.id).into()),
+        };
+
         event_handler
+            .update_interaction_reply(command, None, &[embed], &[])
+            .await?;
+        Ok(())
+    }
+
+    /// Refuse choices with custom emoji from other servers, which the bot can't be sure
+    /// to react with once the poll is posted, and mark the animated ones so they render.
+    async fn check_custom_emoji(
+        &self,
+        guild_id: GuildId,
+        options: &mut [PollOption],
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if !options
+            .iter()
+            .any(|option| matches!(option.emoji, EmojiKey::Custom { .. }))
+        {
+            return Ok(());
+        }
+
+        let emojis = event_handler
             .bot
             .http
-            .create_reaction(
-                original_response.channel_id,
-                original_response.id,
-                &negative,
-            )
+            .emojis(guild_id)
             .exec()
+            .await?
+            .models()
             .await?;
+        for option in options {
+            if let EmojiKey::Custom { id, animated } = &mut option.emoji {
+                match emojis.iter().find(|emoji| emoji.id == *id) {
+                    Some(emoji) => *animated = emoji.animated,
+                    None => {
+                        return Err(UserError::BadInput(format!(
+                            "The emoji for \"{}\" isn't from this server",
+                            option.name
+                        ))
+                        .into())
+                    }
+                }
+            }
+        }
 
         Ok(())
     }
+
+    /// Look up a poll by id, as long as it belongs to this guild.
+    async fn fetch_poll(
+        &self,
+        command: &Box<ApplicationCommand>,
+        id: i64,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<PollRow, Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Polls can only be managed in a guild")?;
+
+        Ok(event_handler
+            .bot
+            .db
+            .poll()
+            .fetch_poll(id)
+            .await?
+            .filter(|poll| poll.guild_id == guild_id.to_string())
+            .ok_or_else(|| UserError::BadInput("There is no poll with that id".into()))?)
+    }
+}
+
+pub struct PollEmojiArgs {
+    pub positive: EmojiKey,
+    pub negative: EmojiKey,
+}
+
+impl CommandArgs for PollEmojiArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(Self {
+            positive: options.required("positive")?,
+            negative: options.required("negative")?,
+        })
+    }
 }
 
-pub struct PollEmojiCommand<'a>(pub &'a Box<ApplicationCommand>);
+pub struct PollEmojiCommand;
+
+#[async_trait]
+impl SlashCommand for PollEmojiCommand {
+    type Args = PollEmojiArgs;
+
+    fn definition(&self) -> Command {
+        chat_input(
+            "poll_emoji",
+            "Set the emoji used to vote on yes/no polls",
+            vec![
+                CommandOption::String(ChoiceCommandOptionData {
+                    choices: vec![],
+                    description: "A custom emoji from this server for \"yes\"".into(),
+                    name: "positive".into(),
+                    required: true,
+                }),
+                CommandOption::String(ChoiceCommandOptionData {
+                    choices: vec![],
+                    description: "A custom emoji from this server for \"no\"".into(),
+                    name: "negative".into(),
+                    required: true,
+                }),
+            ],
+        )
+    }
 
-impl<'a> PollEmojiCommand<'a> {
-    pub async fn process_command(
+    async fn run(
         &self,
-        event_handler: &EventHandler<'a>,
+        command: &Box<ApplicationCommand>,
+        args: PollEmojiArgs,
+        event_handler: &EventHandler<'_>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = match command.guild_id {
+            Some(id) => id,
+            None => return Err("poll_emoji can only be used in a guild".into()),
+        };
+        let member = command
+            .member
+            .as_ref()
+            .ok_or("Poll command has no member")?;
+        if !moderation::is_admin(&event_handler.bot.db, guild_id, member).await? {
+            return Err(UserError::MissingPermissions(
+                "Only admins can change the poll emoji".into(),
+            )
+            .into());
+        }
+
         event_handler
-            .simple_interaction_reply(self.0, "Pong!")
+            .defer_interaction_reply(command, false)
             .await?;
 
+        let emojis = event_handler
+            .bot
+            .http
+            .emojis(guild_id)
+            .exec()
+            .await?
+            .models()
+            .await?;
+
+        // Only the guild's own emoji are guaranteed to be usable for reactions by the bot.
+        let find_guild_emoji = |key: EmojiKey| match key {
+            EmojiKey::Custom { id, .. } => emojis.iter().find(|emoji| emoji.id == id),
+            _ => None,
+        };
+        // Animated emoji only render when mentioned with the `a` prefix.
+        let mention = |emoji: &Emoji| {
+            format!(
+                "<{}:{}:{}>",
+                if emoji.animated { "a" } else { "" },
+                emoji.name,
+                emoji.id
+            )
+        };
+
+        match (
+            find_guild_emoji(args.positive),
+            find_guild_emoji(args.negative),
+        ) {
+            (Some(positive), Some(negative)) => {
+                let reply = format!(
+                    "Polls will now use {} and {}",
+                    mention(positive),
+                    mention(negative)
+                );
+                event_handler
+                    .bot
+                    .db
+                    .poll()
+                    .upsert_poll_options(
+                        guild_id,
+                        (positive.id, positive.name.clone()),
+                        (negative.id, negative.name.clone()),
+                    )
+                    .await?;
+                event_handler
+                    .update_interaction_reply(command, Some(&reply), &[], &[])
+                    .await?;
+            }
+            _ => {
+                event_handler
+                    .update_interaction_reply(
+                        command,
+                        Some("Both emoji must be custom emoji from this server"),
+                        &[],
+                        &[],
+                    )
+                    .await?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,111 @@
+use std::error::Error;
+
+use twilight_model::application::{
+    component::{button::ButtonStyle, ActionRow, Button, Component},
+    interaction::MessageComponentInteraction,
+};
+
+use crate::bot::event_handler::EventHandler;
+
+use super::{
+    results::{tallies_embed, tally_votes},
+    PollOption,
+};
+
+/// Module name that every poll component's custom id starts with.
+pub const COMPONENT_PREFIX: &str = "poll";
+const VOTE_CUSTOM_ID_PREFIX: &str = "poll:vote:";
+
+/// A row of buttons per five choices, each casting a vote for its choice.
+pub fn vote_components(options: &[PollOption]) -> Vec<Component> {
+    options
+        .chunks(5)
+        .enumerate()
+        .map(|(row, options)| {
+            Component::ActionRow(ActionRow {
+                components: options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, option)| {
+                        Component::Button(Button {
+                            custom_id: Some(format!("{}{}", VOTE_CUSTOM_ID_PREFIX, row * 5 + i)),
+                            disabled: false,
+                            emoji: Some(option.emoji.to_reaction_type()),
+                            label: Some(option.name.clone()),
+                            style: ButtonStyle::Primary,
+                            url: None,
+                        })
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Record a button vote, replacing the member's previous vote, and confirm it ephemerally.
+pub async fn handle_component(
+    component: &Box<MessageComponentInteraction>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let position = component
+        .data
+        .custom_id
+        .strip_prefix(VOTE_CUSTOM_ID_PREFIX)
+        .and_then(|position| position.parse::<usize>().ok())
+        .ok_or_else(|| format!("Unknown poll component: {}", component.data.custom_id))?;
+    let user_id = match (&component.member, &component.user) {
+        (Some(member), _) if member.user.is_some() => member.user.as_ref().unwrap().id,
+        (_, Some(user)) => user.id,
+        _ => return Err("Poll vote interaction has no user".into()),
+    };
+
+    let db = &event_handler.bot.db;
+    let poll = match db
+        .poll()
+        .fetch_poll_by_message_id(component.message.id)
+        .await?
+    {
+        Some(poll) => poll,
+        None => {
+            return event_handler
+                .component_interaction_reply(component, "This poll no longer exists")
+                .await
+        }
+    };
+    if poll.closed {
+        return event_handler
+            .component_interaction_reply(component, "This poll has closed")
+            .await;
+    }
+
+    let choices = db.poll().fetch_poll_choices(poll.id).await?;
+    let choice = match choices.get(position) {
+        Some(choice) => choice,
+        None => return Err(format!("Poll {} has no choice {}", poll.id, position).into()),
+    };
+
+    db.poll()
+        .upsert_vote(poll.id, user_id, position as i64)
+        .await?;
+
+    // Answer first so the edit below can't push the reply past Discord's deadline.
+    event_handler
+        .component_interaction_reply(component, &format!("You voted for {}", choice.label()))
+        .await?;
+
+    if !poll.hide_results {
+        if let Some(embed) = component.message.embeds.first() {
+            let counts = db.poll().fetch_vote_counts(poll.id).await?;
+            let embed = tallies_embed(embed.clone(), &tally_votes(&choices, &counts));
+            event_handler
+                .bot
+                .http
+                .update_message(component.message.channel_id, component.message.id)
+                .embeds(&[embed])?
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,20 +1,31 @@
-use std::{collections::HashMap, error::Error};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    error::Error,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use serde::{Deserialize, Serialize};
 
-use twilight_http::{
-    request::{prelude::RequestReactionType, AuditLogReason},
-    Client,
-};
+use twilight_http::{request::AuditLogReason, Client};
 use twilight_model::{
-    channel::{embed::Embed, ReactionType},
+    application::{
+        component::{
+            button::ButtonStyle, select_menu::SelectMenuOption, ActionRow, Button, Component,
+            SelectMenu,
+        },
+        interaction::MessageComponentInteraction,
+    },
+    channel::embed::Embed,
     gateway::payload::{ReactionAdd, ReactionRemove},
-    id::{ChannelId, EmojiId, GuildId, MessageId, RoleId},
+    id::{ChannelId, GuildId, MessageId, RoleId, UserId},
 };
 
-use crate::bot::event_handler::EventHandler;
+use crate::{bot::event_handler::EventHandler, models::emoji::EmojiKey};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ReactionRolesMsg {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<MessageId>,
@@ -24,31 +35,260 @@ pub struct ReactionRolesMsg {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub embeds: Vec<Embed>,
     #[serde(rename = "roles")]
-    pub role_map: HashMap<EmojiId, RoleId>,
+    pub role_map: HashMap<EmojiKey, RoleId>,
+    #[serde(default)]
+    pub mode: ReactionRolesMode,
+    /// The most roles from this message a single member may hold at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub style: ReactionRolesStyle,
+}
+
+/// Module name that every reaction role component's custom id starts with.
+pub const COMPONENT_PREFIX: &str = "reaction_roles";
+const BUTTON_CUSTOM_ID_PREFIX: &str = "reaction_roles:role:";
+const SELECT_CUSTOM_ID: &str = "reaction_roles:select";
+/// Discord allows five action rows of five buttons, or 25 select menu options.
+const MAX_COMPONENT_ROLES: usize = 25;
+
+/// How members pick their roles from the message.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReactionRolesStyle {
+    Reactions,
+    Buttons,
+    SelectMenu,
+}
+
+impl Default for ReactionRolesStyle {
+    fn default() -> Self {
+        ReactionRolesStyle::Reactions
+    }
+}
+
+impl ReactionRolesStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionRolesStyle::Reactions => "reactions",
+            ReactionRolesStyle::Buttons => "buttons",
+            ReactionRolesStyle::SelectMenu => "selectMenu",
+        }
+    }
+}
+
+impl FromStr for ReactionRolesStyle {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reactions" => Ok(ReactionRolesStyle::Reactions),
+            "buttons" => Ok(ReactionRolesStyle::Buttons),
+            "selectMenu" => Ok(ReactionRolesStyle::SelectMenu),
+            _ => Err(format!("Invalid reaction roles style: {}", s).into()),
+        }
+    }
+}
+
+/// How reacting and un-reacting on a message translate to role changes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionRolesMode {
+    /// Reacting adds the role and un-reacting removes it.
+    Normal,
+    /// Like normal, but picking a role removes the member's other roles from the message
+    /// along with their reactions.
+    Unique,
+    /// Reacting adds the role, un-reacting does nothing.
+    Verify,
+    /// Reacting removes the role, un-reacting does nothing.
+    Drop,
+    /// Reacting removes the role and un-reacting adds it.
+    Reversed,
+}
+
+impl Default for ReactionRolesMode {
+    fn default() -> Self {
+        ReactionRolesMode::Normal
+    }
+}
+
+impl ReactionRolesMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionRolesMode::Normal => "normal",
+            ReactionRolesMode::Unique => "unique",
+            ReactionRolesMode::Verify => "verify",
+            ReactionRolesMode::Drop => "drop",
+            ReactionRolesMode::Reversed => "reversed",
+        }
+    }
+}
+
+impl FromStr for ReactionRolesMode {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(ReactionRolesMode::Normal),
+            "unique" => Ok(ReactionRolesMode::Unique),
+            "verify" => Ok(ReactionRolesMode::Verify),
+            "drop" => Ok(ReactionRolesMode::Drop),
+            "reversed" => Ok(ReactionRolesMode::Reversed),
+            _ => Err(format!("Invalid reaction roles mode: {}", s).into()),
+        }
+    }
+}
+
+/// A reaction role message as stored in the `reaction_roles_message` table.
+#[derive(sqlx::FromRow)]
+pub struct ReactionRolesMsgRow {
+    pub message_id: String,
+    pub channel_id: String,
+    pub guild_id: String,
+    pub content: String,
+    pub embeds: String,
+    pub roles: String,
+    pub mode: String,
+    pub role_limit: Option<i64>,
+    pub style: String,
+}
+
+impl TryFrom<ReactionRolesMsgRow> for ReactionRolesMsg {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: ReactionRolesMsgRow) -> Result<Self, Self::Error> {
+        Ok(ReactionRolesMsg {
+            message_id: Some(MessageId(row.message_id.parse()?)),
+            channel_id: ChannelId(row.channel_id.parse()?),
+            guild_id: GuildId(row.guild_id.parse()?),
+            content: row.content,
+            embeds: serde_json::from_str(&row.embeds)?,
+            role_map: serde_json::from_str(&row.roles)?,
+            mode: row.mode.parse()?,
+            limit: row.role_limit.map(|limit| limit as u32),
+            style: row.style.parse()?,
+        })
+    }
 }
 
 impl ReactionRolesMsg {
+    /// Check the message can be posted, returning a message for the dashboard if not.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.role_map.is_empty() {
+            return Err("A reaction role message needs at least one role".into());
+        }
+        if self.limit == Some(0) {
+            return Err("The role limit has to be at least 1".into());
+        }
+        // Buttons and select menu options are identified by their role, so each role can
+        // only be offered once.
+        if self.style != ReactionRolesStyle::Reactions {
+            let roles = self.role_map.values().collect::<HashSet<_>>();
+            if roles.len() < self.role_map.len() {
+                return Err("Buttons and select menus can only offer each role once".into());
+            }
+        }
+        Ok(())
+    }
+
     pub async fn handle_reaction_add(
         &self,
-        reaction_add: ReactionAdd,
+        reaction_add: &ReactionAdd,
         event_handler: &EventHandler<'_>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let emoji_id = match &reaction_add.emoji {
-            ReactionType::Custom {
-                animated: _,
-                id,
-                name: _,
-            } => id,
-            ReactionType::Unicode { name: _ } => return Err("Invalid emoji reaction".into()),
+        let key = EmojiKey::from(&reaction_add.emoji);
+        let role_id = match self.role_map.get(&key) {
+            Some(role_id) => *role_id,
+            None => return Ok(()),
         };
+        let user_id = reaction_add.user_id;
+
+        match self.mode {
+            ReactionRolesMode::Normal | ReactionRolesMode::Verify => {}
+            ReactionRolesMode::Unique => {
+                let member_roles = self.member_roles(reaction_add, event_handler).await?;
+                for (other_key, other_role) in self.role_map.iter() {
+                    if *other_key == key || !member_roles.contains(other_role) {
+                        continue;
+                    }
+                    self.revoke(user_id, *other_role, event_handler).await?;
+                    self.clear_reaction(user_id, other_key, event_handler).await;
+                }
+                return self.grant(user_id, role_id, event_handler).await;
+            }
+            ReactionRolesMode::Drop | ReactionRolesMode::Reversed => {
+                return self.revoke(user_id, role_id, event_handler).await;
+            }
+        }
+
+        if let Some(limit) = self.limit {
+            let member_roles = self.member_roles(reaction_add, event_handler).await?;
+            if self.held_roles(&member_roles, Some(role_id)) >= limit as usize {
+                self.clear_reaction(user_id, &key, event_handler).await;
+                return Ok(());
+            }
+        }
+
+        self.grant(user_id, role_id, event_handler).await
+    }
+
+    pub async fn handle_reaction_remove(
+        &self,
+        reaction_remove: &ReactionRemove,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let key = EmojiKey::from(&reaction_remove.emoji);
+        let role_id = match self.role_map.get(&key) {
+            Some(role_id) => *role_id,
+            None => return Ok(()),
+        };
+        let user_id = reaction_remove.user_id;
+
+        if let Some(message_id) = self.message_id {
+            if event_handler
+                .bot
+                .cleared_reactions
+                .take(message_id, user_id, &key)
+            {
+                return Ok(());
+            }
+        }
+
+        match self.mode {
+            ReactionRolesMode::Normal | ReactionRolesMode::Unique => {
+                self.revoke(user_id, role_id, event_handler).await
+            }
+            ReactionRolesMode::Reversed => {
+                if let Some(limit) = self.limit {
+                    let member = event_handler
+                        .bot
+                        .http
+                        .guild_member(self.guild_id, user_id)
+                        .exec()
+                        .await?
+                        .model()
+                        .await?;
+                    if self.held_roles(&member.roles, Some(role_id)) >= limit as usize {
+                        return Ok(());
+                    }
+                }
+                self.grant(user_id, role_id, event_handler).await
+            }
+            ReactionRolesMode::Verify | ReactionRolesMode::Drop => Ok(()),
+        }
+    }
+
+    async fn grant(
+        &self,
+        user_id: UserId,
+        role_id: RoleId,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         event_handler
             .bot
             .http
-            .add_guild_member_role(
-                reaction_add.guild_id.unwrap(),
-                reaction_add.user_id,
-                self.role_map.get(emoji_id).unwrap().to_owned(),
-            )
+            .add_guild_member_role(self.guild_id, user_id, role_id)
             .reason("Reaction Role")?
             .exec()
             .await?;
@@ -56,27 +296,16 @@ impl ReactionRolesMsg {
         Ok(())
     }
 
-    pub async fn handle_reaction_remove(
+    async fn revoke(
         &self,
-        reaction_remove: ReactionRemove,
+        user_id: UserId,
+        role_id: RoleId,
         event_handler: &EventHandler<'_>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let emoji_id = match &reaction_remove.emoji {
-            ReactionType::Custom {
-                animated: _,
-                id,
-                name: _,
-            } => id,
-            ReactionType::Unicode { name: _ } => return Err("Invalid emoji reaction".into()),
-        };
         event_handler
             .bot
             .http
-            .remove_guild_member_role(
-                reaction_remove.guild_id.unwrap(),
-                reaction_remove.user_id,
-                self.role_map.get(emoji_id).unwrap().to_owned(),
-            )
+            .remove_guild_member_role(self.guild_id, user_id, role_id)
             .reason("Reaction Role")?
             .exec()
             .await?;
@@ -84,19 +313,296 @@ impl ReactionRolesMsg {
         Ok(())
     }
 
+    /// Remove a member's reaction from the message. Failures are only logged, since the
+    /// reaction may already be gone and the role change matters more than the reaction.
+    async fn clear_reaction(
+        &self,
+        user_id: UserId,
+        key: &EmojiKey,
+        event_handler: &EventHandler<'_>,
+    ) {
+        let message_id = match self.message_id {
+            Some(id) => id,
+            None => return,
+        };
+        let cleared = &event_handler.bot.cleared_reactions;
+        cleared.insert(message_id, user_id, key);
+        if let Err(e) = event_handler
+            .bot
+            .http
+            .delete_reaction(
+                self.channel_id,
+                message_id,
+                &key.as_request_reaction(),
+                user_id,
+            )
+            .exec()
+            .await
+        {
+            cleared.take(message_id, user_id, key);
+            eprintln!(
+                "Failed to clear reaction {} from message {}: {}",
+                key, message_id, e
+            );
+        }
+    }
+
+    /// The roles of the member who reacted, falling back to fetching the member
+    /// if the gateway didn't include them.
+    async fn member_roles(
+        &self,
+        reaction_add: &ReactionAdd,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<Vec<RoleId>, Box<dyn Error + Send + Sync>> {
+        if let Some(member) = &reaction_add.member {
+            return Ok(member.roles.clone());
+        }
+        let member = event_handler
+            .bot
+            .http
+            .guild_member(self.guild_id, reaction_add.user_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+        Ok(member.roles)
+    }
+
+    /// Count how many of this message's roles are in `member_roles`, ignoring `except`.
+    fn held_roles(&self, member_roles: &[RoleId], except: Option<RoleId>) -> usize {
+        self.role_map
+            .values()
+            .filter(|role_id| Some(**role_id) != except && member_roles.contains(role_id))
+            .count()
+    }
+
+    /// Toggle the roles picked through one of the message's buttons or its select menu,
+    /// replying ephemerally with what changed.
+    pub async fn handle_component(
+        &self,
+        component: &Box<MessageComponentInteraction>,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let member = match &component.member {
+            Some(member) => member,
+            None => return Err("Reaction role component used outside of a guild".into()),
+        };
+        let user_id = match &member.user {
+            Some(user) => user.id,
+            None => return Err("Reaction role component interaction has no user".into()),
+        };
+        let held = |role_id: &RoleId| member.roles.contains(role_id);
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut limited = false;
+
+        let custom_id = component.data.custom_id.as_str();
+        if custom_id == SELECT_CUSTOM_ID {
+            let selected = component
+                .data
+                .values
+                .iter()
+                .filter_map(|value| value.parse::<u64>().ok().map(RoleId))
+                .collect::<Vec<RoleId>>();
+            for role_id in self.role_map.values() {
+                let picked = selected.contains(role_id);
+                match self.mode {
+                    ReactionRolesMode::Verify => {
+                        if picked && !held(role_id) {
+                            added.push(*role_id);
+                        }
+                    }
+                    ReactionRolesMode::Drop => {
+                        if picked && held(role_id) {
+                            removed.push(*role_id);
+                        }
+                    }
+                    _ => {
+                        if picked && !held(role_id) {
+                            added.push(*role_id);
+                        } else if !picked && held(role_id) {
+                            removed.push(*role_id);
+                        }
+                    }
+                }
+            }
+            if let Some(limit) = self.limit {
+                let kept = self.held_roles(&member.roles, None) - removed.len();
+                let allowed = (limit as usize).saturating_sub(kept);
+                if added.len() > allowed {
+                    added.truncate(allowed);
+                    limited = true;
+                }
+            }
+        } else if let Some(role_id) = custom_id
+            .strip_prefix(BUTTON_CUSTOM_ID_PREFIX)
+            .and_then(|id| id.parse::<u64>().ok())
+            .map(RoleId)
+            .filter(|role_id| self.role_map.values().any(|r| r == role_id))
+        {
+            if held(&role_id) {
+                if self.mode != ReactionRolesMode::Verify {
+                    removed.push(role_id);
+                }
+            } else if self.mode != ReactionRolesMode::Drop {
+                if self.mode == ReactionRolesMode::Unique {
+                    removed.extend(self.role_map.values().filter(|r| held(*r)));
+                    added.push(role_id);
+                } else if self
+                    .limit
+                    .map(|limit| self.held_roles(&member.roles, None) >= limit as usize)
+                    .unwrap_or(false)
+                {
+                    limited = true;
+                } else {
+                    added.push(role_id);
+                }
+            }
+        } else {
+            return Err(format!("Unknown reaction role component: {}", custom_id).into());
+        }
+
+        for role_id in removed.iter() {
+            self.revoke(user_id, *role_id, event_handler).await?;
+        }
+        for role_id in added.iter() {
+            self.grant(user_id, *role_id, event_handler).await?;
+        }
+
+        let mention = |roles: &[RoleId]| {
+            roles
+                .iter()
+                .map(|id| format!("<@&{}>", id))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let mut reply = Vec::new();
+        if !added.is_empty() {
+            reply.push(format!("Added {}", mention(&added)));
+        }
+        if !removed.is_empty() {
+            reply.push(format!("Removed {}", mention(&removed)));
+        }
+        if limited {
+            reply.push(format!(
+                "You can only have {} of these roles at a time",
+                self.limit.unwrap_or_default()
+            ));
+        }
+        if reply.is_empty() {
+            reply.push("Your roles are unchanged".into());
+        }
+
+        event_handler
+            .component_interaction_reply(component, &reply.join("\n"))
+            .await
+    }
+
+    /// Build the buttons or select menu used to pick roles, labelled with the role names.
+    async fn components(
+        &self,
+        http: &Client,
+    ) -> Result<Vec<Component>, Box<dyn Error + Send + Sync>> {
+        if self.style == ReactionRolesStyle::Reactions {
+            return Ok(vec![]);
+        }
+        if self.role_map.len() > MAX_COMPONENT_ROLES {
+            return Err(format!(
+                "Reaction role messages using components can have at most {} roles",
+                MAX_COMPONENT_ROLES
+            )
+            .into());
+        }
+
+        let roles = http.roles(self.guild_id).exec().await?.models().await?;
+        let role_name = |role_id: &RoleId| {
+            roles
+                .iter()
+                .find(|role| role.id == *role_id)
+                .map(|role| role.name.clone())
+                .unwrap_or_else(|| role_id.to_string())
+        };
+        let mut entries = self.role_map.iter().collect::<Vec<(&EmojiKey, &RoleId)>>();
+        entries.sort_by_key(|(_, role_id)| role_name(role_id));
+
+        let components = match self.style {
+            ReactionRolesStyle::Buttons => entries
+                .chunks(5)
+                .map(|row| {
+                    Component::ActionRow(ActionRow {
+                        components: row
+                            .iter()
+                            .map(|(key, role_id)| {
+                                Component::Button(Button {
+                                    custom_id: Some(format!(
+                                        "{}{}",
+                                        BUTTON_CUSTOM_ID_PREFIX, role_id
+                                    )),
+                                    disabled: false,
+                                    emoji: Some(key.to_reaction_type()),
+                                    label: Some(role_name(role_id)),
+                                    style: ButtonStyle::Secondary,
+                                    url: None,
+                                })
+                            })
+                            .collect(),
+                    })
+                })
+                .collect(),
+            ReactionRolesStyle::SelectMenu => {
+                let max_values = match self.mode {
+                    ReactionRolesMode::Unique => 1,
+                    _ => self
+                        .limit
+                        .map(|limit| (limit as usize).min(entries.len()))
+                        .unwrap_or(entries.len()),
+                };
+                vec![Component::ActionRow(ActionRow {
+                    components: vec![Component::SelectMenu(SelectMenu {
+                        custom_id: SELECT_CUSTOM_ID.into(),
+                        disabled: false,
+                        max_values: Some(max_values as u8),
+                        min_values: Some(0),
+                        options: entries
+                            .iter()
+                            .map(|(key, role_id)| SelectMenuOption {
+                                default: false,
+                                description: None,
+                                emoji: Some(key.to_reaction_type()),
+                                label: role_name(role_id),
+                                value: role_id.to_string(),
+                            })
+                            .collect(),
+                        placeholder: Some("Pick your roles".into()),
+                    })],
+                })]
+            }
+            ReactionRolesStyle::Reactions => vec![],
+        };
+
+        Ok(components)
+    }
+
     /// Post the reaction role message to its designated channel.
     /// This will update the message id to the value returned from create_message.
     ///
+    /// The bot's reactions are added separately by [`add_reactions`](Self::add_reactions),
+    /// once the message is saved and reactions to it can be handled.
+    ///
     /// Will Err if the message is already posted.
     pub async fn post(&mut self, http: &Client) -> Result<(), Box<dyn Error + Send + Sync>> {
         if self.message_id.is_some() {
             return Err("Reaction Role already posted: Message ID is not empty".into());
         }
 
+        let components = self.components(http).await?;
+
         let message = http
             .create_message(self.channel_id)
             .content(&self.content)?
             .embeds(&self.embeds)?
+            .components(&components)?
             .exec()
             .await?
             .model()
@@ -104,20 +610,193 @@ impl ReactionRolesMsg {
 
         self.message_id = Some(message.id);
 
-        // react with all of the emoji in the role map
-        for key in self.role_map.keys() {
-            http.create_reaction(
-                self.channel_id,
-                message.id,
-                &RequestReactionType::Custom {
-                    id: *key,
-                    name: None,
-                },
-            )
+        Ok(())
+    }
+
+    /// React to the posted message with all of the emoji in the role map, if it uses
+    /// reactions.
+    ///
+    /// Will Err if the message has not been posted yet.
+    pub async fn add_reactions(&self, http: &Client) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let message_id = match self.message_id {
+            Some(id) => id,
+            None => return Err("Reaction Role not posted: Message ID is empty".into()),
+        };
+
+        if self.style == ReactionRolesStyle::Reactions {
+            for key in self.role_map.keys() {
+                http.create_reaction(self.channel_id, message_id, &key.as_request_reaction())
+                    .exec()
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Edit the posted message to match this one, reacting with any emoji that are new
+    /// compared to `previous` and removing the bot's reactions for ones that were dropped.
+    ///
+    /// Will Err if the message has not been posted yet.
+    pub async fn update(
+        &self,
+        previous: &ReactionRolesMsg,
+        http: &Client,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let message_id = match self.message_id {
+            Some(id) => id,
+            None => return Err("Reaction Role not posted: Message ID is empty".into()),
+        };
+
+        let components = self.components(http).await?;
+
+        http.update_message(self.channel_id, message_id)
+            .content(Some(&self.content))?
+            .embeds(&self.embeds)?
+            .components(Some(&components))?
             .exec()
             .await?;
+
+        let uses_reactions = self.style == ReactionRolesStyle::Reactions;
+        let used_reactions = previous.style == ReactionRolesStyle::Reactions;
+
+        if used_reactions && !uses_reactions {
+            http.delete_all_reactions(self.channel_id, message_id)
+                .exec()
+                .await?;
+        }
+        if !uses_reactions {
+            return Ok(());
+        }
+
+        if used_reactions {
+            for key in previous.role_map.keys() {
+                if !self.role_map.contains_key(key) {
+                    http.delete_current_user_reaction(
+                        self.channel_id,
+                        message_id,
+                        &key.as_request_reaction(),
+                    )
+                    .exec()
+                    .await?;
+                }
+            }
+        }
+        for key in self.role_map.keys() {
+            if !used_reactions || !previous.role_map.contains_key(key) {
+                http.create_reaction(self.channel_id, message_id, &key.as_request_reaction())
+                    .exec()
+                    .await?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// Reactions the bot removed itself, whose removal events mustn't be treated as the member
+/// un-reacting, which would take away a role they may have had all along.
+#[derive(Default)]
+pub struct ClearedReactions(Mutex<HashMap<(MessageId, UserId, EmojiKey), Instant>>);
+
+impl ClearedReactions {
+    /// How long to wait for the removal event before forgetting a cleared reaction.
+    const EXPIRY: Duration = Duration::from_secs(60);
+
+    fn insert(&self, message_id: MessageId, user_id: UserId, key: &EmojiKey) {
+        let mut cleared = self.0.lock().expect("ClearedReactions lock was poisoned");
+        let now = Instant::now();
+        cleared.retain(|_, at| now.duration_since(*at) < Self::EXPIRY);
+        cleared.insert((message_id, user_id, key.clone()), now);
+    }
+
+    /// Forget a cleared reaction, returning whether the bot had cleared it.
+    fn take(&self, message_id: MessageId, user_id: UserId, key: &EmojiKey) -> bool {
+        self.0
+            .lock()
+            .expect("ClearedReactions lock was poisoned")
+            .remove(&(message_id, user_id, key.clone()))
+            .map(|at| at.elapsed() < Self::EXPIRY)
+            .unwrap_or(false)
+    }
+}
+
+pub async fn handle_reaction_add(
+    reaction_add: Box<ReactionAdd>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if reaction_add.guild_id.is_none() || is_current_user(reaction_add.user_id, event_handler) {
+        return Ok(());
+    }
+
+    let message = event_handler
+        .bot
+        .db
+        .reaction_roles()
+        .fetch_message(reaction_add.message_id)
+        .await?;
+
+    if let Some(message) = message {
+        message
+            .handle_reaction_add(&reaction_add, event_handler)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_reaction_remove(
+    reaction_remove: Box<ReactionRemove>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if reaction_remove.guild_id.is_none() || is_current_user(reaction_remove.user_id, event_handler)
+    {
+        return Ok(());
+    }
+
+    let message = event_handler
+        .bot
+        .db
+        .reaction_roles()
+        .fetch_message(reaction_remove.message_id)
+        .await?;
+
+    if let Some(message) = message {
+        message
+            .handle_reaction_remove(&reaction_remove, event_handler)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_component(
+    component: &Box<MessageComponentInteraction>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = event_handler
+        .bot
+        .db
+        .reaction_roles()
+        .fetch_message(component.message.id)
+        .await?;
+
+    match message {
+        Some(message) => message.handle_component(component, event_handler).await,
+        None => {
+            event_handler
+                .component_interaction_reply(component, "This role picker no longer exists")
+                .await
+        }
+    }
+}
+
+/// The bot reacts to its own messages when posting them, which must not grant it roles.
+fn is_current_user(user_id: UserId, event_handler: &EventHandler<'_>) -> bool {
+    event_handler
+        .bot
+        .discord_cache
+        .current_user()
+        .map(|user| user.id == user_id)
+        .unwrap_or(false)
+}
@@ -0,0 +1,251 @@
+mod options;
+pub mod permissions;
+
+use std::{error::Error, fmt::Display};
+
+use async_trait::async_trait;
+use twilight_model::{
+    application::{
+        command::{Command, CommandOption, CommandType},
+        interaction::{application_command::CommandDataOption, ApplicationCommand},
+    },
+    guild::Permissions,
+    id::ApplicationId,
+};
+
+use crate::bot::{errors::UserError, event_handler::EventHandler};
+
+use super::{
+    custom_commands,
+    moderation::{
+        slash_commands::{CaseCommand, CasesCommand, ModCommand},
+        ModAction,
+    },
+    poll::slash_commands::{PollCommand, PollEmojiCommand},
+    timezone::TimezoneCommand,
+};
+
+pub use self::options::{CommandArgs, CommandOptions};
+
+#[derive(Debug)]
+pub enum SlashCommandError {
+    CannotProcessUnknownCommand,
+    MissingOption(String),
+    InvalidOption(String),
+}
+
+impl Display for SlashCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlashCommandError::CannotProcessUnknownCommand => {
+                f.write_str("Cannot process unknown command")
+            }
+            SlashCommandError::MissingOption(name) => {
+                write!(f, "Missing required option `{}`", name)
+            }
+            SlashCommandError::InvalidOption(name) => {
+                write!(f, "Invalid value for option `{}`", name)
+            }
+        }
+    }
+}
+
+impl Error for SlashCommandError {}
+
+/// A chat input command: how it is registered with Discord, the arguments it takes
+/// and what it does when used.
+#[async_trait]
+pub trait SlashCommand: Send + Sync {
+    type Args: CommandArgs + Send;
+
+    /// The command as registered with Discord. The registry fills in the application id.
+    fn definition(&self) -> Command;
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: Self::Args,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Object safe form of [`SlashCommand`], parsing the arguments before running the command.
+#[async_trait]
+trait RegisteredCommand: Send + Sync {
+    async fn process(
+        &self,
+        command: &Box<ApplicationCommand>,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<T: SlashCommand> RegisteredCommand for T {
+    async fn process(
+        &self,
+        command: &Box<ApplicationCommand>,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let args = T::Args::from_options(&CommandOptions::new(&command.data.options))?;
+        self.run(command, args, event_handler).await
+    }
+}
+
+/// Every slash command the bot offers. Used both to register the commands with Discord
+/// and to dispatch them when they are used.
+pub struct SlashCommandRegistry {
+    commands: Vec<(Command, Box<dyn RegisteredCommand>)>,
+}
+
+impl SlashCommandRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self { commands: vec![] };
+        registry.register(PingCommand);
+        registry.register(PollCommand);
+        registry.register(PollEmojiCommand);
+        registry.register(TimezoneCommand);
+        registry.register(ModCommand(ModAction::Warn));
+        registry.register(ModCommand(ModAction::Kick));
+        registry.register(ModCommand(ModAction::Ban));
+        registry.register(ModCommand(ModAction::Tempban));
+        registry.register(ModCommand(ModAction::Mute));
+        registry.register(ModCommand(ModAction::Note));
+        registry.register(CasesCommand);
+        registry.register(CaseCommand);
+        registry
+    }
+
+    fn register<T: SlashCommand + 'static>(&mut self, command: T) {
+        self.commands
+            .push((command.definition(), Box::new(command)));
+    }
+
+    /// The definitions of every command, ready to be set on Discord.
+    pub fn definitions(&self, application_id: ApplicationId) -> Vec<Command> {
+        self.commands
+            .iter()
+            .map(|(definition, _)| Command {
+                application_id: Some(application_id),
+                ..definition.clone()
+            })
+            .collect()
+    }
+
+    /// The name of every command and `<command> <subcommand>` pair, which can each be
+    /// restricted by a [`permissions::CommandPermission`].
+    pub fn command_keys(&self) -> Vec<String> {
+        let mut keys = vec![];
+        for (definition, _) in self.commands.iter() {
+            keys.push(definition.name.clone());
+            for option in definition.options.iter() {
+                if let CommandOption::SubCommand(subcommand) = option {
+                    keys.push(format!("{} {}", definition.name, subcommand.name));
+                }
+            }
+        }
+        keys
+    }
+
+    pub async fn process(
+        &self,
+        command: &Box<ApplicationCommand>,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self
+            .commands
+            .iter()
+            .find(|(definition, _)| definition.name == command.data.name)
+        {
+            Some((_, handler)) => {
+                check_permissions(command, event_handler).await?;
+                handler.process(command, event_handler).await
+            }
+            // Anything else is registered on the guild by the custom commands module.
+            None => {
+                check_permissions(command, event_handler).await?;
+                custom_commands::handle_command(command, event_handler).await
+            }
+        }
+    }
+}
+
+impl Default for SlashCommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Refuse a command when the guild restricted it, or the subcommand used, to members
+/// the invoker isn't one of.
+async fn check_permissions(
+    command: &Box<ApplicationCommand>,
+    event_handler: &EventHandler<'_>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (guild_id, member) = match (command.guild_id, &command.member) {
+        (Some(guild_id), Some(member)) => (guild_id, member),
+        _ => return Ok(()),
+    };
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    let mut keys = vec![command.data.name.clone()];
+    if let Some(CommandDataOption::SubCommand { name, .. }) = command.data.options.first() {
+        keys.push(format!("{} {}", command.data.name, name));
+    }
+
+    for key in keys {
+        let permission = event_handler
+            .bot
+            .db
+            .command_permissions()
+            .fetch(guild_id, &key)
+            .await?;
+        if let Some(permission) = permission {
+            if !permission.allows(permissions, &member.roles) {
+                return Err(UserError::MissingPermissions(format!(
+                    "You don't have permission to use `/{}`",
+                    key
+                ))
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the definition of a chat input command.
+pub fn chat_input(name: &str, description: &str, options: Vec<CommandOption>) -> Command {
+    Command {
+        application_id: None,
+        guild_id: None,
+        name: name.into(),
+        default_permission: None,
+        description: description.into(),
+        id: None,
+        kind: CommandType::ChatInput,
+        options,
+    }
+}
+
+pub struct PingCommand;
+
+#[async_trait]
+impl SlashCommand for PingCommand {
+    type Args = ();
+
+    fn definition(&self) -> Command {
+        chat_input("ping", "Ping Bingus", vec![])
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        _: (),
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        event_handler
+            .simple_interaction_reply(command, "Pong!")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::str::FromStr;
+
+use twilight_model::application::interaction::application_command::CommandDataOption;
+
+use super::SlashCommandError;
+
+/// The options a command was used with, looked up by name.
+pub struct CommandOptions<'a>(&'a [CommandDataOption]);
+
+impl<'a> CommandOptions<'a> {
+    pub fn new(options: &'a [CommandDataOption]) -> Self {
+        Self(options)
+    }
+
+    fn find(&self, name: &str) -> Option<&'a CommandDataOption> {
+        self.0.iter().find(|option| option.name() == name)
+    }
+
+    pub fn string(&self, name: &str) -> Option<&'a str> {
+        match self.find(name) {
+            Some(CommandDataOption::String { value, .. }) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.find(name) {
+            Some(CommandDataOption::Integer { value, .. }) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn boolean(&self, name: &str) -> Option<bool> {
+        match self.find(name) {
+            Some(CommandDataOption::Boolean { value, .. }) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Parse a string option, such as the id of a user, role or channel option.
+    pub fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, SlashCommandError> {
+        self.string(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| SlashCommandError::InvalidOption(name.into()))
+            })
+            .transpose()
+    }
+
+    pub fn required_string(&self, name: &str) -> Result<&'a str, SlashCommandError> {
+        self.string(name)
+            .ok_or_else(|| SlashCommandError::MissingOption(name.into()))
+    }
+
+    pub fn required_integer(&self, name: &str) -> Result<i64, SlashCommandError> {
+        self.integer(name)
+            .ok_or_else(|| SlashCommandError::MissingOption(name.into()))
+    }
+
+    pub fn required<T: FromStr>(&self, name: &str) -> Result<T, SlashCommandError> {
+        self.parse(name)?
+            .ok_or_else(|| SlashCommandError::MissingOption(name.into()))
+    }
+
+    /// The subcommand that was used and its options.
+    pub fn subcommand(&self) -> Result<(&'a str, CommandOptions<'a>), SlashCommandError> {
+        self.0
+            .iter()
+            .find_map(|option| match option {
+                CommandDataOption::SubCommand { name, options } => {
+                    Some((name.as_str(), CommandOptions::new(options)))
+                }
+                _ => None,
+            })
+            .ok_or(SlashCommandError::CannotProcessUnknownCommand)
+    }
+}
+
+/// The typed arguments of a command, read from the options it was used with.
+pub trait CommandArgs: Sized {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError>;
+}
+
+impl CommandArgs for () {
+    fn from_options(_: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::application::interaction::application_command::CommandDataOption;
+
+    use super::CommandOptions;
+
+    #[test]
+    fn test_command_options() {
+        let options = vec![CommandDataOption::SubCommand {
+            name: "end".into(),
+            options: vec![
+                CommandDataOption::Integer {
+                    name: "id".into(),
+                    value: 4,
+                },
+                CommandDataOption::String {
+                    name: "user".into(),
+                    value: "1234".into(),
+                },
+            ],
+        }];
+        let options = CommandOptions::new(&options);
+
+        let (name, options) = options.subcommand().unwrap();
+        assert_eq!(name, "end");
+        assert_eq!(options.required_integer("id").unwrap(), 4);
+        assert_eq!(options.parse::<u64>("user").unwrap(), Some(1234));
+        assert!(options.parse::<u64>("id").unwrap().is_none());
+        assert!(options.required_string("reason").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use std::{convert::TryFrom, error::Error};
+
+use serde::{Deserialize, Serialize};
+use twilight_model::{guild::Permissions, id::RoleId};
+
+/// Who may use a command in a guild. `command` is either a command's name, like `poll`,
+/// or a command and subcommand, like `poll create`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPermission {
+    pub command: String,
+    /// Discord permissions the member must all have, as a bitfield.
+    #[serde(default)]
+    pub permissions: u64,
+    /// Roles the member must have at least one of. Empty means any member.
+    #[serde(default)]
+    pub roles: Vec<RoleId>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct CommandPermissionRow {
+    pub command: String,
+    pub permissions: i64,
+    pub roles: String,
+}
+
+impl TryFrom<CommandPermissionRow> for CommandPermission {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: CommandPermissionRow) -> Result<Self, Self::Error> {
+        Ok(CommandPermission {
+            command: row.command,
+            permissions: row.permissions as u64,
+            roles: serde_json::from_str(&row.roles)?,
+        })
+    }
+}
+
+impl CommandPermission {
+    /// Whether a member with `permissions` and `roles` may use the command.
+    /// Administrators may use every command.
+    pub fn allows(&self, permissions: Permissions, roles: &[RoleId]) -> bool {
+        if permissions.contains(Permissions::ADMINISTRATOR) {
+            return true;
+        }
+
+        permissions.contains(Permissions::from_bits_truncate(self.permissions))
+            && (self.roles.is_empty() || self.roles.iter().any(|role| roles.contains(role)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{guild::Permissions, id::RoleId};
+
+    use super::CommandPermission;
+
+    #[test]
+    fn test_allows() {
+        let permission = CommandPermission {
+            command: "poll create".into(),
+            permissions: Permissions::MANAGE_MESSAGES.bits(),
+            roles: vec![RoleId(1), RoleId(2)],
+        };
+
+        assert!(permission.allows(Permissions::MANAGE_MESSAGES, &[RoleId(2)]));
+        assert!(permission.allows(Permissions::ADMINISTRATOR, &[]));
+        assert!(!permission.allows(Permissions::MANAGE_MESSAGES, &[RoleId(3)]));
+        assert!(!permission.allows(Permissions::SEND_MESSAGES, &[RoleId(1)]));
+    }
+}
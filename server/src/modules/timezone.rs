@@ -0,0 +1,115 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use twilight_model::{
+    application::{
+        command::{BaseCommandOptionData, ChoiceCommandOptionData, Command, CommandOption},
+        interaction::ApplicationCommand,
+    },
+    id::{GuildId, UserId},
+};
+
+use crate::{
+    bot::{errors::UserError, event_handler::EventHandler},
+    db::Database,
+    modules::slash_commands::{
+        chat_input, CommandArgs, CommandOptions, SlashCommand, SlashCommandError,
+    },
+};
+
+/// The timezone to read a member's dates and times in: their own if they set one,
+/// then their guild's, then UTC.
+pub async fn resolve_timezone(
+    db: &Database,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Tz, Box<dyn Error + Send + Sync>> {
+    Ok(db
+        .timezone()
+        .fetch_timezone(guild_id, user_id)
+        .await?
+        .and_then(|timezone| timezone.parse().ok())
+        .unwrap_or(Tz::UTC))
+}
+
+pub struct TimezoneArgs {
+    pub zone: Option<String>,
+    pub reset: bool,
+}
+
+impl CommandArgs for TimezoneArgs {
+    fn from_options(options: &CommandOptions<'_>) -> Result<Self, SlashCommandError> {
+        Ok(Self {
+            zone: options.string("zone").map(String::from),
+            reset: options.boolean("reset").unwrap_or(false),
+        })
+    }
+}
+
+pub struct TimezoneCommand;
+
+#[async_trait]
+impl SlashCommand for TimezoneCommand {
+    type Args = TimezoneArgs;
+
+    fn definition(&self) -> Command {
+        chat_input(
+            "timezone",
+            "Set the timezone your dates and times are read in",
+            vec![
+                CommandOption::String(ChoiceCommandOptionData {
+                    choices: vec![],
+                    description: "A timezone name like Europe/London or America/New_York".into(),
+                    name: "zone".into(),
+                    required: false,
+                }),
+                CommandOption::Boolean(BaseCommandOptionData {
+                    description: "Go back to using the server's timezone".into(),
+                    name: "reset".into(),
+                    required: false,
+                }),
+            ],
+        )
+    }
+
+    async fn run(
+        &self,
+        command: &Box<ApplicationCommand>,
+        args: TimezoneArgs,
+        event_handler: &EventHandler<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let guild_id = command
+            .guild_id
+            .ok_or("Timezones can only be set in a guild")?;
+        let user_id = command
+            .member
+            .as_ref()
+            .and_then(|member| member.user.as_ref())
+            .ok_or("Timezone command has no user")?
+            .id;
+
+        let db = &event_handler.bot.db;
+        if args.reset {
+            db.timezone().delete_user_timezone(user_id).await?;
+        } else if let Some(zone) = &args.zone {
+            let timezone = zone.trim().parse::<Tz>().map_err(|_| {
+                UserError::BadInput(format!(
+                    "Unknown timezone `{}`. Use a name like `Europe/London` or `America/New_York`.",
+                    zone
+                ))
+            })?;
+            db.timezone()
+                .upsert_user_timezone(user_id, timezone.name())
+                .await?;
+        }
+
+        let timezone = resolve_timezone(db, guild_id, user_id).await?;
+        event_handler
+            .ephemeral_interaction_reply(
+                command,
+                &format!("Your times are read in `{}`", timezone.name()),
+            )
+            .await
+    }
+}
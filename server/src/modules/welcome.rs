@@ -453,13 +453,24 @@ async fn member_join_dm_embed(
     Ok(())
 }
 
-fn parse_embed(
+pub fn parse_embed(
+    embed: twilight_model::channel::embed::Embed,
+    guild: &Guild,
+    user: &User,
+    channel: &Channel,
+) -> twilight_model::channel::embed::Embed {
+    parse_embed_with_args(embed, guild, user, channel, &[])
+}
+
+/// Like [`parse_embed`], also filling in the `{args}` and `{argN}` placeholders.
+pub fn parse_embed_with_args(
     mut embed: twilight_model::channel::embed::Embed,
     guild: &Guild,
     user: &User,
     channel: &Channel,
+    args: &[String],
 ) -> twilight_model::channel::embed::Embed {
-    let parse_string = |s: String| parse_message(s.as_str(), guild, user, channel);
+    let parse_string = |s: String| parse_message_with_args(s.as_str(), guild, user, channel, args);
     embed.description = embed.description.map(parse_string);
     embed.title = embed.title.map(parse_string);
     embed.author = embed.author.map(|mut a| {
@@ -471,17 +482,29 @@ fn parse_embed(
         f
     });
     embed.fields.iter_mut().for_each(|f| {
-        f.name = parse_message(f.name.as_str(), guild, user, channel);
-        f.value = parse_message(f.value.as_str(), guild, user, channel);
+        f.name = parse_message_with_args(f.name.as_str(), guild, user, channel, args);
+        f.value = parse_message_with_args(f.value.as_str(), guild, user, channel, args);
     });
     embed
 }
 
-fn parse_message(message: &str, guild: &Guild, user: &User, channel: &Channel) -> String {
+pub fn parse_message(message: &str, guild: &Guild, user: &User, channel: &Channel) -> String {
+    parse_message_with_args(message, guild, user, channel, &[])
+}
+
+/// Like [`parse_message`], also filling in `{args}` with every argument and
+/// `{arg1}`, `{arg2}`, ... with a single one. Missing arguments become empty.
+pub fn parse_message_with_args(
+    message: &str,
+    guild: &Guild,
+    user: &User,
+    channel: &Channel,
+    args: &[String],
+) -> String {
     let mut interpolating = false;
     let mut start_index: usize = 0;
     let mut res = String::new();
-    for (i, c) in message.chars().enumerate() {
+    for (i, c) in message.char_indices() {
         match c {
             '{' => {
                 interpolating = true;
@@ -527,7 +550,11 @@ fn parse_message(message: &str, guild: &Guild, user: &User, channel: &Channel) -
                     "{user.id}" => res += &user.id.to_string(),
                     "{user.idname}" => res += format!("<@{}>", &user.id).as_str(),
                     "{user.mention}" => res += format!("<@!{}>", &user.id).as_str(),
-                    _ => res += &message[start_index..i + 1],
+                    "{args}" => res += &args.join(" "),
+                    placeholder => match argument_index(placeholder) {
+                        Some(index) => res += args.get(index).map(String::as_str).unwrap_or(""),
+                        None => res += placeholder,
+                    },
                 };
                 interpolating = false;
             }
@@ -541,6 +568,16 @@ fn parse_message(message: &str, guild: &Guild, user: &User, channel: &Channel) -
     res
 }
 
+/// Zero-based argument index of an `{argN}` placeholder.
+fn argument_index(placeholder: &str) -> Option<usize> {
+    placeholder
+        .strip_prefix("{arg")?
+        .strip_suffix('}')?
+        .parse::<usize>()
+        .ok()?
+        .checked_sub(1)
+}
+
 #[cfg(test)]
 mod tests {
     use twilight_embed_builder::{EmbedAuthorBuilder, EmbedFieldBuilder, EmbedFooterBuilder};
@@ -551,7 +588,7 @@ mod tests {
         user::User,
     };
 
-    use crate::modules::welcome::parse_message;
+    use crate::modules::welcome::{parse_message, parse_message_with_args};
 
     use super::parse_embed;
 
@@ -647,6 +684,21 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_message_with_args() {
+        let (guild, user, channel) = get_test_data();
+        let args = vec!["first".to_string(), "second".to_string()];
+
+        let parsed = parse_message_with_args(
+            "{user.name}: {arg2} {arg1} | {args} | {arg3}{arg0}",
+            &guild,
+            &user,
+            &channel,
+            &args,
+        );
+        assert_eq!(parsed, "Test User: second first | first second | {arg0}")
+    }
+
     #[test]
     fn test_parse_embed() {
         let (guild, user, channel) = get_test_data();
@@ -0,0 +1,139 @@
+use std::{convert::TryFrom, error::Error, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use twilight_http::Client;
+use twilight_model::id::GuildId;
+
+use crate::{
+    db::Database,
+    modules::{moderation, poll},
+};
+
+/// How often the database is checked for jobs that have come due.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Jobs that keep failing are dropped after this many tries, which with [`retry_delay`]
+/// comes to a few days so they outlast a Discord outage or the bot being kicked by mistake.
+const MAX_ATTEMPTS: i64 = 20;
+/// The longest a failed job waits before it's tried again.
+const MAX_RETRY_DELAY_MINUTES: i64 = 6 * 60;
+/// How long a guild's settings and history are kept after the bot is removed, so a
+/// mistaken kick or a re-invite doesn't lose them.
+pub const GUILD_RETENTION_DAYS: i64 = 30;
+
+/// Work to do at a later time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Job {
+    /// Lift a temporary ban or mute once its duration has passed.
+    #[serde(rename_all = "camelCase")]
+    ExpireCase { case_id: i64 },
+    /// Close a poll and show its results once its end time has passed.
+    #[serde(rename_all = "camelCase")]
+    ClosePoll { poll_id: i64 },
+    /// Delete everything stored for a guild once the bot has been out of it for
+    /// [`GUILD_RETENTION_DAYS`], cancelled if it's added back before then.
+    #[serde(rename_all = "camelCase")]
+    PurgeGuild { guild_id: GuildId },
+}
+
+impl Job {
+    async fn run(&self, db: &Database, http: &Client) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self {
+            Job::ExpireCase { case_id } => moderation::expire_case(*case_id, db, http).await,
+            Job::ClosePoll { poll_id } => poll::scheduler::expire_poll(*poll_id, db, http).await,
+            Job::PurgeGuild { guild_id } => Ok(db.guild().remove_guild(*guild_id).await?),
+        }
+    }
+}
+
+/// A job stored in the `scheduled_job` table, so it still runs if the bot restarts before
+/// it's due. The table also keeps the guild and due time, which are only used in queries.
+#[derive(Clone, Debug)]
+pub struct ScheduledJob {
+    pub id: i64,
+    pub job: Job,
+    pub attempts: i64,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ScheduledJobRow {
+    pub id: i64,
+    pub job: String,
+    pub attempts: i64,
+}
+
+impl TryFrom<ScheduledJobRow> for ScheduledJob {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(row: ScheduledJobRow) -> Result<Self, Self::Error> {
+        Ok(ScheduledJob {
+            id: row.id,
+            job: serde_json::from_str(&row.job)?,
+            attempts: row.attempts,
+        })
+    }
+}
+
+/// Run scheduled jobs as they come due, forever.
+///
+/// Jobs are read from the database on every check, so ones scheduled before a restart are
+/// picked up again, and any that came due while the bot was offline run straight away.
+pub async fn run(db: Database, http: Client) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = run_due_jobs(&db, &http).await {
+            eprintln!("Failed to run scheduled jobs: {}", e);
+        }
+    }
+}
+
+/// How long to wait before trying a job again after it failed `attempts` times, doubling
+/// from a minute up to [`MAX_RETRY_DELAY_MINUTES`].
+fn retry_delay(attempts: i64) -> chrono::Duration {
+    let minutes = 1 << (attempts - 1).clamp(0, 16);
+    chrono::Duration::minutes(minutes.min(MAX_RETRY_DELAY_MINUTES))
+}
+
+async fn run_due_jobs(db: &Database, http: &Client) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let jobs = db
+        .scheduled_jobs()
+        .fetch_due_jobs(Utc::now().naive_utc())
+        .await?;
+    for job in jobs {
+        match job.job.run(db, http).await {
+            Ok(()) => db.scheduled_jobs().delete_job(job.id).await?,
+            Err(e) if job.attempts + 1 >= MAX_ATTEMPTS => {
+                eprintln!(
+                    "Giving up on scheduled job {} after {} attempts: {}",
+                    job.id, MAX_ATTEMPTS, e
+                );
+                db.scheduled_jobs().delete_job(job.id).await?
+            }
+            Err(e) => {
+                eprintln!("Failed to run scheduled job {}: {}", job.id, e);
+                let retry_at = Utc::now().naive_utc() + retry_delay(job.attempts + 1);
+                db.scheduled_jobs().record_failure(job.id, retry_at).await?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::{retry_delay, MAX_RETRY_DELAY_MINUTES};
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::minutes(1));
+        assert_eq!(retry_delay(2), Duration::minutes(2));
+        assert_eq!(retry_delay(5), Duration::minutes(16));
+        assert_eq!(retry_delay(19), Duration::minutes(MAX_RETRY_DELAY_MINUTES));
+        assert_eq!(retry_delay(100), Duration::minutes(MAX_RETRY_DELAY_MINUTES));
+    }
+}
@@ -1 +1,2 @@
 pub mod cdn;
+pub mod time;
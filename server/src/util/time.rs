@@ -0,0 +1,207 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use date_time_parser::{DateParser, TimeParser};
+
+#[derive(Debug, PartialEq)]
+pub enum TimeParseError {
+    Unrecognized(String),
+    InPast(String),
+    TooFar(String),
+}
+
+impl Display for TimeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeParseError::Unrecognized(input) => write!(
+                f,
+                "Couldn't understand \"{}\" as a time. Try something like `30m`, `2h`, `3d` or `tomorrow 5pm`.",
+                input
+            ),
+            TimeParseError::InPast(input) => write!(f, "\"{}\" is in the past", input),
+            TimeParseError::TooFar(input) => write!(f, "\"{}\" is too far in the future", input),
+        }
+    }
+}
+
+impl std::error::Error for TimeParseError {}
+
+/// Parse a relative duration such as `30m`, `2h`, `1h 30m` or `in 3 days`.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    let mut chars = input
+        .strip_prefix("in ")
+        .unwrap_or(&input)
+        .chars()
+        .peekable();
+    let mut total = Duration::zero();
+    let mut parts = 0;
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+            number.push(c);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+            unit.push(c);
+        }
+
+        let number = number.parse::<u32>().ok()? as i64;
+        let seconds = match unit.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+            "d" | "day" | "days" => 24 * 60 * 60,
+            "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        total = total.checked_add(&Duration::seconds(number * seconds))?;
+        parts += 1;
+    }
+
+    if parts == 0 {
+        None
+    } else {
+        Some(total)
+    }
+}
+
+/// Write a duration out in words, largest units first, like `1 hour 30 minutes`.
+pub fn format_duration(duration: Duration) -> String {
+    let mut seconds = duration.num_seconds().max(0);
+    let mut parts = vec![];
+    for (unit, length) in [
+        ("week", 7 * 24 * 60 * 60),
+        ("day", 24 * 60 * 60),
+        ("hour", 60 * 60),
+        ("minute", 60),
+        ("second", 1),
+    ] {
+        let count = seconds / length;
+        seconds %= length;
+        if count > 0 {
+            parts.push(format!(
+                "{} {}{}",
+                count,
+                unit,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    if parts.is_empty() {
+        "0 seconds".into()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Parse a point in the future, either relative to `now` (see [`parse_duration`]) or an
+/// absolute date and/or time like `tomorrow 5pm` read as wall-clock time in `timezone`.
+///
+/// A time without a date means its next occurrence, and a date without a time means midnight.
+pub fn parse_time(
+    input: &str,
+    now: DateTime<Utc>,
+    timezone: Tz,
+) -> Result<DateTime<Utc>, TimeParseError> {
+    let time = match parse_duration(input) {
+        Some(duration) => now
+            .checked_add_signed(duration)
+            .ok_or_else(|| TimeParseError::TooFar(input.into()))?,
+        None => {
+            let local_now = now.with_timezone(&timezone).naive_local();
+            let date = DateParser::parse_relative(input, local_now.date());
+            let time = TimeParser::parse(input);
+            let local = match (date, time) {
+                (None, None) => return Err(TimeParseError::Unrecognized(input.into())),
+                (None, Some(time)) if local_now.time() < time => local_now.date().and_time(time),
+                (None, Some(time)) => local_now.date().succ().and_time(time),
+                (Some(date), None) => date.and_time(NaiveTime::from_hms(0, 0, 0)),
+                (Some(date), Some(time)) => date.and_time(time),
+            };
+            timezone
+                .from_local_datetime(&local)
+                .earliest()
+                .ok_or_else(|| TimeParseError::Unrecognized(input.into()))?
+                .with_timezone(&Utc)
+        }
+    };
+
+    if time <= now {
+        return Err(TimeParseError::InPast(input.into()));
+    }
+
+    Ok(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use chrono_tz::{America::New_York, UTC};
+
+    use super::{format_duration, parse_duration, parse_time, TimeParseError};
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("2h"), Some(Duration::hours(2)));
+        assert_eq!(parse_duration("3d"), Some(Duration::days(3)));
+        assert_eq!(
+            parse_duration("1h 30m"),
+            Some(Duration::hours(1) + Duration::minutes(30))
+        );
+        assert_eq!(parse_duration("in 2 hours"), Some(Duration::hours(2)));
+        assert_eq!(parse_duration("tomorrow"), None);
+        assert_eq!(parse_duration("5pm"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(90)), "1 hour 30 minutes");
+        assert_eq!(format_duration(Duration::days(8)), "1 week 1 day");
+        assert_eq!(format_duration(Duration::seconds(2)), "2 seconds");
+        assert_eq!(format_duration(Duration::zero()), "0 seconds");
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = Utc.ymd(2021, 7, 1).and_hms(12, 0, 0);
+
+        assert_eq!(
+            parse_time("2h", now, UTC),
+            Ok(Utc.ymd(2021, 7, 1).and_hms(14, 0, 0))
+        );
+        // 5pm in New York is 21:00 UTC during daylight saving time
+        assert_eq!(
+            parse_time("5pm", now, New_York),
+            Ok(Utc.ymd(2021, 7, 1).and_hms(21, 0, 0))
+        );
+        // 7am has already passed in New York, so it means tomorrow morning
+        assert_eq!(
+            parse_time("7am", now, New_York),
+            Ok(Utc.ymd(2021, 7, 2).and_hms(11, 0, 0))
+        );
+        assert_eq!(
+            parse_time("0m", now, UTC),
+            Err(TimeParseError::InPast("0m".into()))
+        );
+        assert_eq!(
+            parse_time("4294967295w", now, UTC),
+            Err(TimeParseError::TooFar("4294967295w".into()))
+        );
+        assert_eq!(
+            parse_time("whenever", now, UTC),
+            Err(TimeParseError::Unrecognized("whenever".into()))
+        );
+    }
+}